@@ -15,15 +15,33 @@ pub struct CacheCursor<'a, C: Cursor> {
     pos: usize,
 }
 
+impl<C: Cursor> CacheFile<C> {
+    /// Cache the items of `cursor` and every cursor following it, which are read lazily as
+    /// cursors into this file are resolved
+    pub fn new(cursor: C) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(Vec::new())),
+            head: Arc::new(Mutex::new(Some(cursor))),
+        }
+    }
+}
+
 impl<C: Cursor> CacheFile<C>
 where
     C::Item: Clone,
 {
     pub fn head<'a>(&'a self) -> anyhow::Result<Option<CacheCursor<'a, C>>> {
-        if self.ensure_len(1)? {
-            Ok(Some(CacheCursor { file: self, pos: 0 }))
-        } else {
-            Ok(None)
+        self.at(0)
+    }
+
+    /// Get a cursor at the absolute position `pos`, or [`None`] if `pos` is past the end of the
+    /// file
+    ///
+    /// Once the cache extends to `pos`, this does not touch the underlying cursor at all
+    pub fn at<'a>(&'a self, pos: usize) -> anyhow::Result<Option<CacheCursor<'a, C>>> {
+        match pos.checked_add(1) {
+            Some(len) if self.ensure_len(len)? => Ok(Some(CacheCursor { file: self, pos })),
+            _ => Ok(None),
         }
     }
 }
@@ -85,32 +103,229 @@ where
                     "Cannot apply {op:?} to cursor - Operation would result in overflow"
                 )
             })?,
+            Seek::To(x) => x,
         };
 
-        match self.file.ensure_len(new_pos + 1) {
-            Ok(true) => Ok(Some(Self {
-                file: self.file,
-                pos: new_pos,
-            })),
-            Ok(false) => Ok(None),
-            Err(e) => Err(e),
-        }
+        self.file.at(new_pos)
     }
 }
 
 impl<F: Cursor> CacheFile<F> {
+    /// Extend the cache until it holds at least `len` items, returning `false` if <eof> is reached
+    /// first
+    ///
+    /// When both are needed, `head` is always locked before `data`, and `data` is never held while
+    /// calling into the underlying cursor. That cursor may take locks of its own, e.g. when it is a
+    /// [`CacheCursor`] into another [`CacheFile`], so holding `data` across the call would block
+    /// every reader of this file on it. `head` stays locked while reading so only one caller
+    /// advances it at a time.
     fn ensure_len(&self, len: usize) -> anyhow::Result<bool> {
-        let mut data = self.data.lock().expect("Failed to get guard");
+        if self.cached_len() >= len {
+            return Ok(true);
+        }
 
         let mut maybe_head = self.head.lock().expect("Failed to get guard");
 
-        while data.len() < len
+        while self.cached_len() < len
             && let Some(head) = maybe_head.clone()
         {
-            data.push(head.data()?);
+            let item = head.data()?;
             *maybe_head = head.seek(Seek::Right(1))?;
+            self.data.lock().expect("Failed to get guard").push(item);
+        }
+
+        Ok(self.cached_len() >= len)
+    }
+
+    fn cached_len(&self) -> usize {
+        self.data.lock().expect("Failed to get guard").len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        sync::{Arc, Barrier, mpsc},
+        time::Duration,
+    };
+
+    use crate::{
+        cache_file::CacheFile,
+        cursor::{Cursor, Seek},
+        memory_file::{MemoryCursor, MemoryFile},
+    };
+
+    /// Counts calls to [`Cursor::data`] on the wrapped cursor
+    #[derive(Clone)]
+    struct CountingCursor<C> {
+        inner: C,
+        reads: Rc<Cell<usize>>,
+    }
+
+    impl<C: Cursor> Cursor for CountingCursor<C> {
+        type Item = C::Item;
+
+        fn data(&self) -> anyhow::Result<Self::Item> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.data()
+        }
+
+        fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+            Ok(self.inner.seek(op)?.map(|inner| Self {
+                inner,
+                reads: self.reads.clone(),
+            }))
+        }
+    }
+
+    #[test]
+    fn second_walk_is_served_from_cache() {
+        let v = [0, 1, 2, 3, 4, 5];
+        let f = MemoryFile::new(v.as_slice());
+        let reads = Rc::new(Cell::new(0));
+        let cache = CacheFile::new(CountingCursor {
+            inner: f.head().unwrap().unwrap(),
+            reads: reads.clone(),
+        });
+
+        for _ in 0..2 {
+            let mut data = Vec::new();
+            let mut head = cache.head().unwrap();
+            while let Some(c) = head {
+                data.push(c.data().unwrap());
+                head = c.next().unwrap();
+            }
+
+            assert!(data == v, "{data:?}");
+            assert!(reads.get() == v.len(), "{} reads", reads.get());
+        }
+    }
+
+    #[test]
+    fn random_access_after_population() {
+        let v = (0..100).map(|i| i * 3).collect::<Vec<u32>>();
+        let f = MemoryFile::new(v.as_slice());
+        let reads = Rc::new(Cell::new(0));
+        let cache = CacheFile::new(CountingCursor {
+            inner: f.head().unwrap().unwrap(),
+            reads: reads.clone(),
+        });
+
+        assert!(cache.at(v.len() - 1).unwrap().is_some());
+        assert!(reads.get() == v.len());
+
+        let mut pos = 17usize;
+        for _ in 0..20 {
+            pos = (pos * 31 + 7) % v.len();
+            let cursor = cache.at(pos).unwrap().unwrap();
+            assert!(cursor.data().unwrap() == v[pos]);
+        }
+
+        assert!(reads.get() == v.len());
+        assert!(cache.at(v.len()).unwrap().is_none());
+        assert!(cache.at(usize::MAX).unwrap().is_none());
+    }
+
+    #[test]
+    fn cache_of_cache_walks() {
+        let v = (0..1000).collect::<Vec<u32>>();
+        let f = MemoryFile::new(v.as_slice());
+        let inner = CacheFile::new(f.head().unwrap().unwrap());
+        let outer = CacheFile::new(inner.head().unwrap().unwrap());
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut data = Vec::new();
+                    let mut head = outer.head().unwrap();
+                    while let Some(c) = head {
+                        data.push(c.data().unwrap());
+                        head = c.next().unwrap();
+                    }
+                    assert!(data == v);
+                });
+            }
+
+            let mut data = Vec::new();
+            let mut head = inner.head().unwrap();
+            while let Some(c) = head {
+                data.push(c.data().unwrap());
+                head = c.next().unwrap();
+            }
+            assert!(data == v);
+        });
+    }
+
+    /// Blocks inside [`Cursor::data`] for the item `5` until released
+    #[derive(Clone)]
+    struct BlockingCursor<'a> {
+        inner: MemoryCursor<'a, u32>,
+        entered: Arc<Barrier>,
+        release: Arc<Barrier>,
+    }
+
+    impl<'a> Cursor for BlockingCursor<'a> {
+        type Item = u32;
+
+        fn data(&self) -> anyhow::Result<u32> {
+            let item = self.inner.data()?;
+            if item == 5 {
+                self.entered.wait();
+                self.release.wait();
+            }
+            Ok(item)
         }
 
-        return Ok(data.len() >= len);
+        fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+            Ok(self.inner.seek(op)?.map(|inner| Self {
+                inner,
+                entered: self.entered.clone(),
+                release: self.release.clone(),
+            }))
+        }
+    }
+
+    #[test]
+    fn cached_reads_do_not_wait_on_inner_cursor() {
+        let v = (0..10).collect::<Vec<u32>>();
+        let f = MemoryFile::new(v.as_slice());
+        let entered = Arc::new(Barrier::new(2));
+        let release = Arc::new(Barrier::new(2));
+        let cache = CacheFile::new(BlockingCursor {
+            inner: f.head().unwrap().unwrap(),
+            entered: entered.clone(),
+            release: release.clone(),
+        });
+
+        cache.at(2).unwrap().unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::scope(|s| {
+            s.spawn(|| cache.at(5).unwrap().unwrap().data().unwrap());
+            entered.wait();
+
+            // the reader above is now blocked inside the inner cursor
+            s.spawn(|| tx.send(cache.at(1).unwrap().unwrap().data().unwrap()));
+            let res = rx.recv_timeout(Duration::from_secs(5));
+
+            release.wait();
+            assert!(res == Ok(1), "{res:?}");
+        });
+    }
+
+    #[test]
+    fn seek_to_absolute() {
+        let v = [0, 1, 2, 3, 4, 5];
+        let f = MemoryFile::new(v.as_slice());
+        let cache = CacheFile::new(f.head().unwrap().unwrap());
+        let mid = cache.at(3).unwrap().unwrap();
+
+        assert!(mid.seek(Seek::To(0)).unwrap().unwrap() == cache.head().unwrap().unwrap());
+        assert!(mid.seek(Seek::To(5)).unwrap().unwrap().data().unwrap() == 5);
+        assert!(mid.seek(Seek::To(6)).unwrap().is_none());
+        assert!(mid.seek(Seek::To(usize::MAX)).unwrap().is_none());
+        assert!(mid.seek(Seek::Right(usize::MAX - 3)).unwrap().is_none());
     }
 }
@@ -2,29 +2,247 @@ use crate::cursor::Cursor;
 
 pub trait CharCursorExt: Cursor<Item = char> {
     fn lookahead_match(&self, pattern: &str) -> anyhow::Result<(bool, Option<Self>)>;
+
+    /// Same as [`CharCursorExt::lookahead_match`], but characters are compared by their lowercase
+    /// mapping, so e.g. `"FN"` matches `fn`
+    ///
+    /// This is per-char lowercasing ([`char::to_lowercase`]), not Unicode case folding. Chars
+    /// whose folding differs from their lowercase form do not match, e.g. `ſ` does not match `"s"`,
+    /// and since pattern and input chars are compared one to one, `ß` does not match `"SS"`
+    fn lookahead_match_ci(&self, pattern: &str) -> anyhow::Result<(bool, Option<Self>)>;
+
+    /// Test each of `patterns` with [`CharCursorExt::lookahead_match`], returning the index of the
+    /// longest pattern which matched and the cursor following it
+    ///
+    /// If several patterns of the same length match, the first of them is selected
+    fn lookahead_match_any(
+        &self,
+        patterns: &[&str],
+    ) -> anyhow::Result<(Option<usize>, Option<Self>)>;
+
+    /// Get up to `n` characters starting at this cursor, stopping early at <eof>
+    fn peek(&self, n: usize) -> anyhow::Result<String>;
+
+    /// Collect characters for as long as `pred` holds, returning them along with the cursor at the
+    /// first character which did not match, or [`None`] if the run continued until <eof>
+    fn consume_while<F: Fn(char) -> bool>(&self, pred: F)
+    -> anyhow::Result<(String, Option<Self>)>;
 }
 
 impl<C: Cursor<Item = char>> CharCursorExt for C {
     fn lookahead_match(&self, pattern: &str) -> anyhow::Result<(bool, Option<Self>)> {
-        // weird order of operations here ensures we correctly return true if
-        // a string terminates in <eof>, but all characters match
-        let mut head = Some(self.clone());
+        lookahead_match_by(self, pattern, |a, b| a == b)
+    }
 
-        for char in pattern.chars() {
-            // check for eof first
-            let h = match head {
-                Some(h) => h,
-                None => return Ok((false, None)),
-            };
+    fn lookahead_match_ci(&self, pattern: &str) -> anyhow::Result<(bool, Option<Self>)> {
+        lookahead_match_by(self, pattern, |a, b| a.to_lowercase().eq(b.to_lowercase()))
+    }
+
+    fn lookahead_match_any(
+        &self,
+        patterns: &[&str],
+    ) -> anyhow::Result<(Option<usize>, Option<Self>)> {
+        let mut best: Option<(usize, usize, Option<Self>)> = None;
 
-            // then check validity in advance
-            let data = h.data()?;
-            if data != char {
-                return Ok((false, None));
+        for (i, pattern) in patterns.iter().enumerate() {
+            let len = pattern.chars().count();
+            if best
+                .as_ref()
+                .is_some_and(|(_, best_len, _)| *best_len >= len)
+            {
+                continue;
             }
+
+            if let (true, next) = self.lookahead_match(pattern)? {
+                best = Some((i, len, next));
+            }
+        }
+
+        match best {
+            Some((i, _, next)) => Ok((Some(i), next)),
+            None => Ok((None, None)),
+        }
+    }
+
+    fn peek(&self, n: usize) -> anyhow::Result<String> {
+        let mut out = String::new();
+        let mut head = Some(self.clone());
+
+        for _ in 0..n {
+            let Some(h) = head else {
+                break;
+            };
+            out.push(h.data()?);
             head = h.next()?;
         }
 
-        Ok((true, head))
+        Ok(out)
+    }
+
+    fn consume_while<F: Fn(char) -> bool>(
+        &self,
+        pred: F,
+    ) -> anyhow::Result<(String, Option<Self>)> {
+        let (chars, next) = self.take_while(|c| pred(*c))?;
+        Ok((chars.into_iter().collect(), next))
+    }
+}
+
+fn lookahead_match_by<C: Cursor<Item = char>>(
+    cursor: &C,
+    pattern: &str,
+    eq: impl Fn(char, char) -> bool,
+) -> anyhow::Result<(bool, Option<C>)> {
+    // weird order of operations here ensures we correctly return true if
+    // a string terminates in <eof>, but all characters match
+    let mut head = Some(cursor.clone());
+
+    for char in pattern.chars() {
+        // check for eof first
+        let h = match head {
+            Some(h) => h,
+            None => return Ok((false, None)),
+        };
+
+        // then check validity in advance
+        let data = h.data()?;
+        if !eq(data, char) {
+            return Ok((false, None));
+        }
+        head = h.next()?;
+    }
+
+    Ok((true, head))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{char_cursor_ext::CharCursorExt, cursor::Cursor, memory_file::MemoryFile};
+
+    #[test]
+    fn consume_while_stops_at_mismatch() {
+        let v = "123abc".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (digits, next) = head.consume_while(|c| c.is_ascii_digit()).unwrap();
+
+        assert!(digits == "123");
+        assert!(next.unwrap().data().unwrap() == 'a');
+    }
+
+    #[test]
+    fn consume_while_reaches_eof() {
+        let v = "  \t\n".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (whitespace, next) = head.consume_while(char::is_whitespace).unwrap();
+
+        assert!(whitespace == "  \t\n");
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn consume_while_empty_run() {
+        let v = "abc".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (digits, next) = head.consume_while(|c| c.is_ascii_digit()).unwrap();
+
+        assert!(digits.is_empty());
+        assert!(next.unwrap() == head);
+    }
+
+    #[test]
+    fn lookahead_match_any_prefers_longest() {
+        let v = "<<= x".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (idx, next) = head.lookahead_match_any(&["<", "<<", "<<="]).unwrap();
+        assert!(idx == Some(2));
+        assert!(next.unwrap().data().unwrap() == ' ');
+
+        let (idx, next) = head.lookahead_match_any(&["<<=", "<", "<<"]).unwrap();
+        assert!(idx == Some(0));
+        assert!(next.unwrap().data().unwrap() == ' ');
+
+        let v = "<< x".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (idx, next) = head.lookahead_match_any(&["<", "<<", "<<="]).unwrap();
+        assert!(idx == Some(1));
+        assert!(next.unwrap().data().unwrap() == ' ');
+    }
+
+    #[test]
+    fn lookahead_match_any_prefers_longest_over_order() {
+        let v = "==".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (idx, next) = head.lookahead_match_any(&["==", "="]).unwrap();
+        assert!(idx == Some(0));
+        assert!(next.is_none());
+
+        let (idx, next) = head.lookahead_match_any(&["=", "=="]).unwrap();
+        assert!(idx == Some(1));
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn lookahead_match_any_none_match() {
+        let v = "abc".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (idx, next) = head.lookahead_match_any(&["<", "<<", "<<="]).unwrap();
+        assert!(idx.is_none());
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn lookahead_match_ci_ignores_case() {
+        let v = "fn x".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (matched, next) = head.lookahead_match_ci("FN").unwrap();
+        assert!(matched);
+        assert!(next.unwrap().data().unwrap() == ' ');
+
+        assert!(!head.lookahead_match("FN").unwrap().0);
+        assert!(!head.lookahead_match_ci("FX").unwrap().0);
+    }
+
+    #[test]
+    fn lookahead_match_ci_non_ascii() {
+        let v = "ÉCOLE".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (matched, next) = head.lookahead_match_ci("école").unwrap();
+        assert!(matched);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn peek_stops_at_n_or_eof() {
+        let v = "abcd".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        assert!(head.peek(3).unwrap() == "abc");
+        assert!(head.peek(0).unwrap().is_empty());
+        assert!(head.data().unwrap() == 'a');
+
+        let v = "ab".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        assert!(head.peek(10).unwrap() == "ab");
     }
 }
@@ -9,6 +9,8 @@ pub enum Seek {
     Left(usize),
     /// Seek right-ward towards the end of the file
     Right(usize),
+    /// Seek to an absolute position, where `0` refers to the start of the file
+    To(usize),
 }
 
 /// Cheaaply clonable representation of a single element in some stream of items.
@@ -31,8 +33,9 @@ pub trait Cursor: Clone + Sized {
 
     /// Get a [`Cursor`] at a position relative to this one, or [`None`], indicating that no such
     /// cursor exists. If left seeking is supported, but seek would refer to memory further left than the
-    /// start of the [`File`], this function is expected to return [`None`]. Unsupported
-    /// seek operations are expected to error, rather than result in <eof>
+    /// start of the [`File`], this function is expected to return [`None`]. Likewise, a
+    /// [`Seek::To`] referring to a position past the end of the [`File`] returns [`None`].
+    /// Unsupported seek operations are expected to error, rather than result in <eof>
     ///
     /// Similar to [`Cursor::data`], there is no guarentee that this is a cheap operation and, as is
     /// the case for most [`File`] implementations, may require iterating and parsing each element between
@@ -42,4 +45,123 @@ pub trait Cursor: Clone + Sized {
     fn next(&self) -> anyhow::Result<Option<Self>> {
         self.seek(Seek::Right(1))
     }
+
+    /// Get the cursor `n` items to the right of this one, or [`None`] if that is past <eof>
+    ///
+    /// Equivalent to calling [`Cursor::next`] `n` times, but as a single seek, so implementations
+    /// which support random access don't need to visit each item in between
+    fn skip(&self, n: usize) -> anyhow::Result<Option<Self>> {
+        self.seek(Seek::Right(n))
+    }
+
+    /// Advance past every item for which `f` holds, returning the first cursor for which it does
+    /// not, or [`None`] if <eof> was reached first
+    fn skip_while<F: Fn(&Self::Item) -> bool>(&self, f: F) -> anyhow::Result<Option<Self>> {
+        let mut head = Some(self.clone());
+
+        while let Some(h) = head {
+            if !f(&h.data()?) {
+                return Ok(Some(h));
+            }
+            head = h.next()?;
+        }
+
+        Ok(None)
+    }
+
+    /// Collect items for as long as `f` holds, returning them along with the first cursor for
+    /// which it does not, or [`None`] if <eof> was reached first
+    ///
+    /// Returns the items rather than a [`Span`] since a span cannot end at <eof>
+    fn take_while<F: Fn(&Self::Item) -> bool>(
+        &self,
+        f: F,
+    ) -> anyhow::Result<(Vec<Self::Item>, Option<Self>)> {
+        let mut out = Vec::new();
+        let mut head = Some(self.clone());
+
+        while let Some(h) = head {
+            let data = h.data()?;
+            if !f(&data) {
+                return Ok((out, Some(h)));
+            }
+            out.push(data);
+            head = h.next()?;
+        }
+
+        Ok((out, None))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use crate::{cursor::Cursor, memory_file::MemoryFile};
+
+    #[test]
+    fn skip_while_skips_leading_zeros() {
+        let v = [0u8, 0, 0, 7, 0, 1];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let next = head.skip_while(|b| *b == 0).unwrap().unwrap();
+        assert!(next.data().unwrap() == 7);
+
+        let v = [0u8, 0];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        assert!(head.skip_while(|b| *b == 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn take_while_takes_ascending_run() {
+        let v = [1u8, 2, 5, 9, 3, 4];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let prev = Cell::new(0u8);
+        let (run, next) = head
+            .take_while(|b| {
+                let ascending = *b > prev.get();
+                prev.set(*b);
+                ascending
+            })
+            .unwrap();
+
+        assert!(run == vec![1, 2, 5, 9], "{run:?}");
+        assert!(next.unwrap().data().unwrap() == 3);
+    }
+
+    #[test]
+    fn skip_lands_on_item() {
+        let v = [10u8, 11, 12, 13, 14];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        assert!(head.skip(0).unwrap().unwrap() == head);
+        assert!(head.skip(3).unwrap().unwrap().data().unwrap() == 13);
+        assert!(head.skip(4).unwrap().unwrap().data().unwrap() == 14);
+        assert!(head.skip(5).unwrap().is_none());
+        assert!(head.skip(usize::MAX).unwrap().is_none());
+    }
+
+    #[test]
+    fn take_while_less_than_three() {
+        let v = [0u8, 1, 2, 3, 0];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (run, next) = head.take_while(|b| *b < 3).unwrap();
+        assert!(run == vec![0, 1, 2], "{run:?}");
+        assert!(next.unwrap().data().unwrap() == 3);
+
+        let v = [2u8, 1, 0];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let (run, next) = head.take_while(|b| *b < 3).unwrap();
+        assert!(run == v, "{run:?}");
+        assert!(next.is_none());
+    }
 }
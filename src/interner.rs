@@ -0,0 +1,85 @@
+use std::{collections::HashMap, sync::Arc};
+
+/// Handle to a string stored in an [`Interner`]
+///
+/// Symbols are only meaningful to the [`Interner`] which produced them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+/// Deduplicates strings such as identifier names, handing out a cheap [`Symbol`] for each distinct
+/// string
+///
+/// Each distinct string is allocated once, and shared between the symbol table and its lookup key
+#[derive(Default)]
+pub struct Interner {
+    strings: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the [`Symbol`] for `s`, storing it if it has not been seen before
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(sym) = self.symbols.get(s) {
+            return *sym;
+        }
+
+        let sym = Symbol(
+            u32::try_from(self.strings.len())
+                .expect("Interner cannot hold more than u32::MAX strings"),
+        );
+        let s = Arc::<str>::from(s);
+        self.strings.push(s.clone());
+        self.symbols.insert(s, sym);
+        sym
+    }
+
+    /// Get the string `sym` was interned from
+    ///
+    /// Panics if `sym` was produced by a different [`Interner`] with fewer strings
+    pub fn resolve(&self, sym: Symbol) -> &str {
+        &self.strings[sym.0 as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::interner::Interner;
+
+    #[test]
+    fn intern_deduplicates() {
+        let mut interner = Interner::new();
+
+        let foo = interner.intern("foo");
+        let bar = interner.intern("bar");
+
+        assert!(interner.intern("foo") == foo);
+        assert!(foo != bar);
+    }
+
+    #[test]
+    fn resolve_round_trips() {
+        let mut interner = Interner::new();
+
+        for s in ["foo", "bar", "", "ünïcödé"] {
+            let sym = interner.intern(s);
+            assert!(interner.resolve(sym) == s);
+        }
+    }
+
+    #[test]
+    fn strings_are_stored_once() {
+        let mut interner = Interner::new();
+        interner.intern("foo");
+        interner.intern("foo");
+
+        let (key, _) = interner.symbols.get_key_value("foo").unwrap();
+        assert!(interner.strings.len() == 1);
+        assert!(Arc::ptr_eq(key, &interner.strings[0]));
+    }
+}
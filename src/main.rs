@@ -11,9 +11,11 @@ use crate::{cursor::Cursor, read_seek_file::ReadSeekFile};
 mod cache_file;
 mod char_cursor_ext;
 mod cursor;
+mod interner;
 mod memory_file;
 mod read_seek_file;
 mod span;
+mod token;
 mod utf8_file;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -11,9 +11,14 @@ impl<'a, T> MemoryFile<'a, T> {
     pub fn new(data: &'a [T]) -> Self {
         Self { inner: data }
     }
+
+    /// Borrow the item at `pos`, or [`None`] if `pos` is past the end of the file
+    pub fn get_ref(&self, pos: usize) -> Option<&'a T> {
+        self.inner.get(pos)
+    }
 }
 
-struct MemoryCursor<'a, T> {
+pub struct MemoryCursor<'a, T> {
     file: &'a MemoryFile<'a, T>,
     pos: usize,
 }
@@ -45,8 +50,8 @@ impl<'a, T> Clone for MemoryCursor<'a, T> {
     }
 }
 
-impl<'a, T: Clone> MemoryFile<'a, T> {
-    pub fn head(&'a self) -> anyhow::Result<Option<impl Cursor<Item = T>>> {
+impl<'a, T> MemoryFile<'a, T> {
+    pub fn head(&'a self) -> anyhow::Result<Option<MemoryCursor<'a, T>>> {
         if self.inner.is_empty() {
             Ok(None)
         } else {
@@ -55,45 +60,55 @@ impl<'a, T: Clone> MemoryFile<'a, T> {
     }
 }
 
-impl<'a, T: Clone> Cursor for MemoryCursor<'a, T> {
-    type Item = T;
-
-    fn data(&self) -> anyhow::Result<Self::Item> {
-        self.file.inner.get(self.pos).cloned().ok_or_else(|| {
+impl<'a, T> MemoryCursor<'a, T> {
+    /// Borrow the data associated with this cursor rather than cloning it as [`Cursor::data`]
+    /// does, making this usable for items which do not implement [`Clone`]
+    pub fn data_ref(&self) -> anyhow::Result<&'a T> {
+        self.file.get_ref(self.pos).ok_or_else(|| {
             anyhow::anyhow!("Failed to get data associated with cursor at {}", self.pos)
         })
     }
 
-    fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
-        if let Seek::Left(x) = op {
-            if x > self.pos {
-                Ok(None)
-            } else {
-                Ok(Some(MemoryCursor {
-                    file: self.file,
-                    pos: self.pos - x,
-                }))
-            }
-        } else if let Seek::Right(x) = op {
-            let new_pos = self.pos.checked_add(x).ok_or_else(|| {
+    /// Same as [`Cursor::seek`], but usable for items which do not implement [`Clone`]
+    pub fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+        let new_pos = match op {
+            Seek::Left(x) if x <= self.pos => self.pos - x,
+            Seek::Left(_) => return Ok(None),
+            Seek::Right(x) => self.pos.checked_add(x).ok_or_else(|| {
                 anyhow::anyhow!(
                     "Failed to apply {op:?} to cursor at {}, operation would result in overflow",
                     self.pos
                 )
-            })?;
-
-            if self.file.inner.len() > new_pos {
-                Ok(Some(MemoryCursor {
-                    file: self.file,
-                    pos: new_pos,
-                }))
-            } else {
-                Ok(None)
-            }
+            })?,
+            Seek::To(x) => x,
+        };
+
+        if self.file.inner.len() > new_pos {
+            Ok(Some(MemoryCursor {
+                file: self.file,
+                pos: new_pos,
+            }))
         } else {
-            panic!("Invalid seek operation: {op:?}")
+            Ok(None)
         }
     }
+
+    /// Same as [`Cursor::next`], but usable for items which do not implement [`Clone`]
+    pub fn next(&self) -> anyhow::Result<Option<Self>> {
+        MemoryCursor::seek(self, Seek::Right(1))
+    }
+}
+
+impl<'a, T: Clone> Cursor for MemoryCursor<'a, T> {
+    type Item = T;
+
+    fn data(&self) -> anyhow::Result<Self::Item> {
+        self.data_ref().cloned()
+    }
+
+    fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+        MemoryCursor::seek(self, op)
+    }
 }
 
 #[cfg(test)]
@@ -101,6 +116,7 @@ mod test {
     use crate::{
         cursor::{Cursor, Seek},
         memory_file::MemoryFile,
+        span::SpanTo,
     };
 
     #[test]
@@ -117,4 +133,72 @@ mod test {
             head = c.seek(Seek::Right(1)).expect("Failed to seek right");
         }
     }
+
+    #[test]
+    fn span_collects_data() {
+        let v = [10, 20, 30, 40];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let end = head.seek(Seek::Right(3)).unwrap().unwrap();
+
+        let span = head.span_to(&end).expect("Failed to create span");
+        let data = span
+            .data()
+            .unwrap()
+            .collect::<anyhow::Result<Vec<i32>>>()
+            .expect("Failed to iterate span");
+
+        assert!(data == vec![10, 20, 30], "{data:?}");
+    }
+
+    #[test]
+    fn span_collects_string() {
+        let v = "hello world".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let end = head.seek(Seek::Right(5)).unwrap().unwrap();
+
+        let span = head.span_to(&end).expect("Failed to create span");
+
+        assert!(span.try_to_string().unwrap() == "hello");
+    }
+
+    #[test]
+    fn seek_to_absolute() {
+        let v = [0, 1, 2, 3, 4, 5];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let mid = head.seek(Seek::Right(3)).unwrap().unwrap();
+
+        assert!(mid.seek(Seek::To(0)).unwrap().unwrap().data().unwrap() == 0);
+        assert!(mid.seek(Seek::To(5)).unwrap().unwrap().data().unwrap() == 5);
+        assert!(mid.seek(Seek::To(6)).unwrap().is_none());
+    }
+
+    #[test]
+    fn borrows_non_clone_data() {
+        struct NoClone(u32);
+
+        let v = [NoClone(1), NoClone(2), NoClone(3)];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let mut items = Vec::new();
+        let mut cursor = Some(head.clone());
+        while let Some(c) = cursor {
+            items.push(c.data_ref().unwrap().0);
+            cursor = c.next().unwrap();
+        }
+        assert!(items == [1, 2, 3], "{items:?}");
+
+        let last = head.seek(Seek::To(2)).unwrap().unwrap();
+        assert!(last.data_ref().unwrap().0 == 3);
+        assert!(last.seek(Seek::Left(2)).unwrap().unwrap() == head);
+        assert!(head.seek(Seek::Right(3)).unwrap().is_none());
+
+        assert!(head.data_ref().unwrap().0 == 1);
+        assert!(f.get_ref(2).unwrap().0 == 3);
+        assert!(f.get_ref(3).is_none());
+        assert!(std::ptr::eq(head.data_ref().unwrap(), &v[0]));
+    }
 }
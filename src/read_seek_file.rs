@@ -1,15 +1,30 @@
 use std::{
-    io::{ErrorKind, Read, Seek, SeekFrom},
+    io::{Read, Seek, SeekFrom},
     sync::{Arc, Mutex},
 };
 
 use crate::cursor::{self, Cursor};
 
-/// Adapts an object implementing [`Read`] and [`Seek`] as a [`File`] without caching.
+/// Maximum number of bytes requested from the inner object by a single read
+const READ_AHEAD: usize = 512;
+
+/// Adapts an object implementing [`Read`] and [`Seek`] as a [`File`] without caching beyond a
+/// small read-ahead window.
 ///
 /// Errors produced by calls into the inner object will result
 pub struct ReadSeekFile<R: Read + Seek> {
-    inner: Arc<Mutex<R>>,
+    inner: Arc<Mutex<ReadAhead<R>>>,
+}
+
+/// The inner object of a [`ReadSeekFile`] along with a small window of bytes read ahead of the
+/// last position that was seeked to, so that sequential traversal does not require a seek and a
+/// read for every byte
+///
+/// The window is replaced whenever a position outside of it is requested
+struct ReadAhead<R> {
+    reader: R,
+    start: usize,
+    buf: Vec<u8>,
 }
 
 pub struct ReadSeekCursor<'a, R: Read + Seek> {
@@ -20,22 +35,54 @@ pub struct ReadSeekCursor<'a, R: Read + Seek> {
 impl<R: Read + Seek> From<R> for ReadSeekFile<R> {
     fn from(value: R) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(value)),
+            inner: Arc::new(Mutex::new(ReadAhead {
+                reader: value,
+                start: 0,
+                buf: Vec::with_capacity(READ_AHEAD),
+            })),
         }
     }
 }
 
 impl<'a, R: Read + Seek + 'a> ReadSeekFile<R> {
-    pub fn start(&'a self) -> anyhow::Result<Option<impl Cursor<Item = u8>>> {
-        let mut inner = self.inner.lock().expect("Failed to acquire lock");
-        match inner.seek(SeekFrom::Start(0)) {
-            Ok(_) => Ok(Some(ReadSeekCursor { file: self, pos: 0 })),
-            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
-            Err(e) => Err(e.into()),
+    pub fn start(&'a self) -> anyhow::Result<Option<ReadSeekCursor<'a, R>>> {
+        if self.byte_at(0)?.is_some() {
+            Ok(Some(ReadSeekCursor { file: self, pos: 0 }))
+        } else {
+            Ok(None)
         }
     }
 }
 
+impl<R: Read + Seek> ReadSeekFile<R> {
+    /// Get the byte at `pos`, or [`None`] if `pos` is at or beyond <eof>
+    ///
+    /// [`Seek`] permits seeking beyond the end of a stream, so this is what bounds cursors to
+    /// <eof>
+    fn byte_at(&self, pos: usize) -> anyhow::Result<Option<u8>> {
+        self.inner
+            .lock()
+            .expect("Failed to acquire lock")
+            .byte_at(pos)
+    }
+}
+
+impl<R: Read + Seek> ReadAhead<R> {
+    fn byte_at(&mut self, pos: usize) -> anyhow::Result<Option<u8>> {
+        if pos < self.start || pos >= self.start + self.buf.len() {
+            self.reader.seek(SeekFrom::Start(pos as u64))?;
+            self.start = pos;
+            self.buf.clear();
+            self.reader
+                .by_ref()
+                .take(READ_AHEAD as u64)
+                .read_to_end(&mut self.buf)?;
+        }
+
+        Ok(self.buf.get(pos - self.start).copied())
+    }
+}
+
 impl<'a, R: Read + Seek + 'a> Clone for ReadSeekCursor<'a, R> {
     fn clone(&self) -> Self {
         Self {
@@ -45,15 +92,31 @@ impl<'a, R: Read + Seek + 'a> Clone for ReadSeekCursor<'a, R> {
     }
 }
 
+impl<'a, R: Read + Seek + 'a> PartialEq for ReadSeekCursor<'a, R> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.file, other.file) && self.pos == other.pos
+    }
+}
+
+impl<'a, R: Read + Seek + 'a> Eq for ReadSeekCursor<'a, R> {}
+
+impl<'a, R: Read + Seek + 'a> PartialOrd for ReadSeekCursor<'a, R> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if std::ptr::eq(self.file, other.file) {
+            self.pos.partial_cmp(&other.pos)
+        } else {
+            None
+        }
+    }
+}
+
 impl<'a, R: Read + Seek + 'a> Cursor for ReadSeekCursor<'a, R> {
     type Item = u8;
 
     fn data(&self) -> anyhow::Result<Self::Item> {
-        let mut inner = self.file.inner.lock().expect("Failed to acquire lock");
-        inner.seek(SeekFrom::Start(self.pos as u64))?;
-        let mut data = [0u8];
-        inner.read_exact(&mut data)?;
-        Ok(data[0])
+        self.file.byte_at(self.pos)?.ok_or_else(|| {
+            anyhow::anyhow!("Failed to get data at cursor {}: found <eof>", self.pos)
+        })
     }
 
     fn seek(&self, op: cursor::Seek) -> anyhow::Result<Option<Self>> {
@@ -63,21 +126,163 @@ impl<'a, R: Read + Seek + 'a> Cursor for ReadSeekCursor<'a, R> {
             cursor::Seek::Right(x) => self.pos.checked_add(x).ok_or_else(|| {
                 anyhow::anyhow!("Failed to apply {op:?} - Opretion would result in overflow")
             })?,
+            cursor::Seek::To(x) => x,
         };
 
-        match self
-            .file
-            .inner
-            .lock()
-            .expect("Failed to acquire lock")
-            .seek(SeekFrom::Start(new_pos as u64))
-        {
-            Ok(_) => Ok(Some(Self {
+        if self.file.byte_at(new_pos)?.is_some() {
+            Ok(Some(Self {
                 file: self.file,
                 pos: new_pos,
-            })),
-            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
-            Err(e) => Err(e.into()),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::Cell,
+        io::{self, Read, SeekFrom},
+        rc::Rc,
+    };
+
+    use crate::{
+        cursor::{Cursor, Seek},
+        read_seek_file::ReadSeekFile,
+        span::SpanTo,
+    };
+
+    /// Counts calls to [`io::Seek::seek`] on the wrapped stream
+    struct CountingSeek {
+        inner: io::Cursor<Vec<u8>>,
+        seeks: Rc<Cell<usize>>,
+    }
+
+    impl Read for CountingSeek {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl io::Seek for CountingSeek {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.seeks.set(self.seeks.get() + 1);
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn span_iterates_bytes() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(b"hello world".to_vec()));
+        let head = file.start().unwrap().unwrap();
+        let start = head.seek(Seek::Right(6)).unwrap().unwrap();
+        let end = head.seek(Seek::Right(10)).unwrap().unwrap();
+
+        let span = start.span_to(&end).expect("Failed to create span");
+        let data = span
+            .data()
+            .unwrap()
+            .collect::<anyhow::Result<Vec<u8>>>()
+            .expect("Failed to iterate span");
+
+        assert!(data == b"worl", "{data:?}");
+        assert!(span.len().unwrap() == 4);
+    }
+
+    #[test]
+    fn span_rejects_reversed_cursors() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(b"hello".to_vec()));
+        let head = file.start().unwrap().unwrap();
+        let next = head.next().unwrap().unwrap();
+
+        assert!(next.span_to(&head).is_err());
+    }
+
+    #[test]
+    fn span_slices_stream() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(vec![0u8, 1, 2, 3, 4, 5, 6]));
+        let head = file.start().unwrap().unwrap();
+        let start = head.seek(Seek::Right(2)).unwrap().unwrap();
+        let end = head.seek(Seek::Right(5)).unwrap().unwrap();
+
+        let data = start
+            .span_to(&end)
+            .unwrap()
+            .data()
+            .unwrap()
+            .collect::<anyhow::Result<Vec<u8>>>()
+            .unwrap();
+
+        assert!(data == vec![2, 3, 4], "{data:?}");
+    }
+
+    #[test]
+    fn seek_stops_at_eof() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(vec![0u8, 1, 2]));
+        let head = file.start().unwrap().unwrap();
+
+        assert!(head.seek(Seek::Right(2)).unwrap().is_some());
+        assert!(head.seek(Seek::Right(3)).unwrap().is_none());
+
+        let mut count = 0;
+        let mut cursor = Some(head);
+        while let Some(c) = cursor {
+            c.data().expect("Failed to read byte before <eof>");
+            count += 1;
+            cursor = c.next().unwrap();
+        }
+        assert!(count == 3);
+    }
+
+    #[test]
+    fn empty_stream_has_no_start() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(Vec::<u8>::new()));
+
+        assert!(file.start().unwrap().is_none());
+    }
+
+    #[test]
+    fn seek_to_absolute() {
+        let file = ReadSeekFile::from(std::io::Cursor::new(vec![0u8, 1, 2, 3, 4, 5]));
+        let head = file.start().unwrap().unwrap();
+        let mid = head.seek(Seek::Right(3)).unwrap().unwrap();
+
+        assert!(mid.seek(Seek::To(0)).unwrap().unwrap().data().unwrap() == 0);
+        assert!(mid.seek(Seek::To(5)).unwrap().unwrap().data().unwrap() == 5);
+        assert!(mid.seek(Seek::To(6)).unwrap().is_none());
+    }
+
+    #[test]
+    fn sequential_walk_batches_reads() {
+        let bytes = (0..10 * 1024).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        let seeks = Rc::new(Cell::new(0));
+        let file = ReadSeekFile::from(CountingSeek {
+            inner: io::Cursor::new(bytes.clone()),
+            seeks: seeks.clone(),
+        });
+
+        let mut read = Vec::new();
+        let mut cursor = file.start().unwrap();
+        while let Some(c) = cursor {
+            read.push(c.data().unwrap());
+            cursor = c.next().unwrap();
+        }
+
+        assert!(read == bytes);
+        assert!(seeks.get() < bytes.len() / 100, "{} seeks", seeks.get());
+    }
+
+    #[test]
+    fn non_sequential_seek_refills() {
+        let bytes = (0..2048).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        let file = ReadSeekFile::from(io::Cursor::new(bytes.clone()));
+        let head = file.start().unwrap().unwrap();
+
+        for pos in [1500, 3, 2047, 600, 0] {
+            let c = head.seek(Seek::To(pos)).unwrap().unwrap();
+            assert!(c.data().unwrap() == bytes[pos]);
         }
     }
 }
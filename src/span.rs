@@ -1,6 +1,9 @@
 use std::{cmp::Ordering, marker::PhantomData};
 
-use crate::cursor::{Cursor, Seek};
+use crate::{
+    cursor::{Cursor, Seek},
+    utf8_file::UTF8Cursor,
+};
 
 pub struct Span<C> {
     start: C,
@@ -66,3 +69,221 @@ impl<C: Cursor> Span<C> {
         })
     }
 }
+
+impl<C: Cursor + PartialOrd> Span<C> {
+    /// Number of items between the start (inclusive) and end (exclusive) of this span
+    ///
+    /// [`Cursor`] only guarentees single-step traversal, so this walks the span without resolving
+    /// any data
+    pub fn len(&self) -> anyhow::Result<usize> {
+        let mut len = 0usize;
+        let mut head = self.start.clone();
+
+        while head < self.end {
+            head = head
+                .next()?
+                .ok_or_else(|| anyhow::anyhow!("Reached <eof> while measuring span"))?;
+            len += 1;
+        }
+
+        Ok(len)
+    }
+
+    /// Returns true if this span covers no items, i.e. it was created between two equal cursors
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns true if this span and `other` cover the same sequence of items
+    ///
+    /// Unlike comparing the bounding cursors, this resolves the data of both spans, so spans over
+    /// different files compare equal when their contents match
+    pub fn content_eq<D>(&self, other: &Span<D>) -> anyhow::Result<bool>
+    where
+        D: Cursor<Item = C::Item> + PartialOrd,
+        C::Item: PartialEq,
+    {
+        let mut lhs = self.data()?;
+        let mut rhs = other.data()?;
+
+        loop {
+            match (lhs.next().transpose()?, rhs.next().transpose()?) {
+                (None, None) => return Ok(true),
+                (Some(a), Some(b)) if a == b => {}
+                _ => return Ok(false),
+            }
+        }
+    }
+}
+
+impl<C: Cursor<Item = char> + PartialOrd> Span<C> {
+    /// Collect the characters covered by this span into a [`String`], failing on the first error
+    pub fn try_to_string(&self) -> anyhow::Result<String> {
+        self.data()?.collect()
+    }
+}
+
+impl<C: Cursor<Item = u8> + PartialOrd> Span<UTF8Cursor<C>> {
+    /// Number of bytes between the start (inclusive) and end (exclusive) of this span
+    ///
+    /// Unlike [`Span::len`], this is computed from the byte offsets of the bounding cursors, so no
+    /// chars are decoded
+    ///
+    /// Both cursors are always converted from the same byte cursor, as [`UTF8Cursor`]s from
+    /// different conversions cannot be compared and so cannot form a span
+    pub fn byte_len(&self) -> usize {
+        self.end.byte_pos() - self.start.byte_pos()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cursor::{Cursor, Seek},
+        memory_file::{MemoryCursor, MemoryFile},
+        span::SpanTo,
+        utf8_file::UTF8Cursor,
+    };
+
+    #[test]
+    fn len_matches_data() {
+        let v = [0, 1, 2, 3, 4, 5, 6, 7];
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        for i in 0..v.len() {
+            let end = head.seek(Seek::Right(i)).unwrap().unwrap();
+            let span = head.span_to(&end).unwrap();
+            let count = span.data().unwrap().count();
+
+            assert!(span.len().unwrap() == i);
+            assert!(count == i, "{count} != {i}");
+            assert!(span.is_empty() == (i == 0));
+        }
+    }
+
+    #[test]
+    fn byte_len_of_utf8_span() {
+        for (input, bytes, chars) in [("hello", 5, 5), ("héllo wörld", 13, 11), ("日本語", 9, 3)]
+        {
+            let v = input.bytes().collect::<Vec<u8>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = UTF8Cursor::convert(f.head().unwrap().unwrap())
+                .unwrap()
+                .unwrap();
+            let end = head.seek(Seek::Right(chars - 1)).unwrap().unwrap();
+            let span = head.span_to(&end).unwrap();
+
+            // spans exclude their end, so the last char is not counted
+            let last = end.data().unwrap().len_utf8();
+            assert!(span.byte_len() == bytes - last, "{input}");
+            assert!(span.len().unwrap() == chars - 1, "{input}");
+        }
+    }
+
+    #[test]
+    fn try_to_string_round_trips_utf8() {
+        let input = "héllo, wörld! 日本語.";
+        let v = input.bytes().collect::<Vec<u8>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = UTF8Cursor::convert(f.head().unwrap().unwrap())
+            .unwrap()
+            .unwrap();
+        let end = head
+            .seek(Seek::Right(input.chars().count() - 1))
+            .unwrap()
+            .unwrap();
+
+        let span = head.span_to(&end).unwrap();
+        assert!(span.try_to_string().unwrap() == input.trim_end_matches('.'));
+    }
+
+    /// Fails to resolve data for `'!'`
+    #[derive(Clone, PartialEq, PartialOrd)]
+    struct FailingCursor<'a>(MemoryCursor<'a, char>);
+
+    impl<'a> Cursor for FailingCursor<'a> {
+        type Item = char;
+
+        fn data(&self) -> anyhow::Result<char> {
+            match self.0.data()? {
+                '!' => Err(anyhow::anyhow!("Failed to get data at cursor")),
+                c => Ok(c),
+            }
+        }
+
+        fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+            Ok(self.0.seek(op)?.map(FailingCursor))
+        }
+    }
+
+    #[test]
+    fn try_to_string_stops_at_first_error() {
+        let v = "ab!cd".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = FailingCursor(f.head().unwrap().unwrap());
+        let end = head.skip(4).unwrap().unwrap();
+
+        assert!(
+            head.span_to(&head.skip(2).unwrap().unwrap())
+                .unwrap()
+                .try_to_string()
+                .unwrap()
+                == "ab"
+        );
+        assert!(head.span_to(&end).unwrap().try_to_string().is_err());
+    }
+
+    #[test]
+    fn content_eq_across_files() {
+        let a = "abc".chars().collect::<Vec<char>>();
+        let b = "abc".chars().collect::<Vec<char>>();
+        let c = "axc".chars().collect::<Vec<char>>();
+        let (fa, fb, fc) = (
+            MemoryFile::new(a.as_slice()),
+            MemoryFile::new(b.as_slice()),
+            MemoryFile::new(c.as_slice()),
+        );
+        let ha = fa.head().unwrap().unwrap();
+        let hb = fb.head().unwrap().unwrap();
+        let hc = fc.head().unwrap().unwrap();
+
+        let span_a = ha.span_to(&ha.skip(2).unwrap().unwrap()).unwrap();
+        let span_b = hb.span_to(&hb.skip(2).unwrap().unwrap()).unwrap();
+        assert!(span_a.content_eq(&span_b).unwrap());
+        // the same items, but not the same place
+        assert!(ha != hb);
+
+        let shorter = hb.span_to(&hb.next().unwrap().unwrap()).unwrap();
+        let different = hc.span_to(&hc.skip(2).unwrap().unwrap()).unwrap();
+        assert!(!span_a.content_eq(&shorter).unwrap());
+        assert!(!span_a.content_eq(&different).unwrap());
+    }
+
+    #[test]
+    fn byte_len_requires_same_origin() {
+        let v = "héllo".bytes().collect::<Vec<u8>>();
+        let f = MemoryFile::new(v.as_slice());
+        let bytes = f.head().unwrap().unwrap();
+
+        let start = UTF8Cursor::convert(bytes.clone())
+            .unwrap()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let other = UTF8Cursor::convert(bytes.skip(4).unwrap().unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(start.span_to(&other).is_err());
+
+        let end = start.skip(2).unwrap().unwrap();
+        // same byte, but measured from different places
+        assert!(end.byte_pos() == 4 && other.byte_pos() == 0);
+        assert!(end != other);
+
+        let span = start.span_to(&end).unwrap();
+        assert!(span.byte_len() == 3);
+        assert!(span.try_to_string().unwrap() == "él");
+    }
+}
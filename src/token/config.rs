@@ -0,0 +1,31 @@
+/// Options controlling how a [`Tokenizer`] splits source into tokens
+///
+/// [`Tokenizer`]: crate::token::Tokenizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenizerConfig {
+    /// Treat `\/*` and `\*/` inside of a block comment as escaped delimiters, which do not change
+    /// the nesting depth of the comment
+    pub comment_escapes: bool,
+    /// Produce [`Whitespace`] tokens, rather than skipping over them
+    ///
+    /// [`Whitespace`]: crate::token::Whitespace
+    pub keep_whitespace: bool,
+    /// Deepest a block comment may nest before it is rejected as an error
+    pub max_comment_depth: usize,
+    /// Identifiers munched as [`Identifier::Keyword`] rather than [`Identifier::Standard`]
+    ///
+    /// [`Identifier::Keyword`]: crate::token::Identifier::Keyword
+    /// [`Identifier::Standard`]: crate::token::Identifier::Standard
+    pub keywords: &'static [&'static str],
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        Self {
+            comment_escapes: true,
+            keep_whitespace: true,
+            max_comment_depth: 256,
+            keywords: &[],
+        }
+    }
+}
@@ -1,15 +1,31 @@
+mod config;
+mod tokenizer;
 mod variants;
 
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData};
 
+pub use config::*;
+pub use tokenizer::*;
 pub use variants::*;
 
 use crate::cursor::Cursor;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Punct(char);
 
-#[derive(Clone)]
+impl Punct {
+    /// The punctuation character this token was munched from
+    pub fn char(&self) -> char {
+        self.0
+    }
+
+    /// Returns true if this token is the punctuation character `c`
+    pub fn is(&self, c: char) -> bool {
+        self.0 == c
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub enum Tok {
     Whitespace(Whitespace),
     Identifier(Identifier),
@@ -17,6 +33,55 @@ pub enum Tok {
     Punct(Punct),
 }
 
+/// The kind of a [`Tok`], without its payload
+///
+/// Punctuation keeps its character, since parsers almost always care which punctuation they found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Whitespace,
+    Identifier,
+    Literal,
+    Punct(char),
+}
+
+/// Renders the token as it was spelled in the source, so concatenating every token of a file
+/// reproduces it exactly
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Whitespace(
+                Whitespace::Standard(s) | Whitespace::LineComment(s) | Whitespace::BlockComment(s),
+            ) => f.write_str(s),
+            Tok::Identifier(Identifier::Raw(s, _)) => write!(f, "r#{s}"),
+            Tok::Identifier(Identifier::Standard(s, _) | Identifier::Keyword(s, _)) => {
+                f.write_str(s)
+            }
+            Tok::Literal(
+                Literal::Char(_, raw)
+                | Literal::RawChar(_, raw)
+                | Literal::String(_, raw)
+                | Literal::RawString(_, raw)
+                | Literal::ByteString(_, raw)
+                | Literal::CString(_, raw)
+                | Literal::Integer(_, raw)
+                | Literal::Decimal(_, raw),
+            ) => f.write_str(raw),
+            Tok::Punct(p) => write!(f, "{}", p.char()),
+        }
+    }
+}
+
+impl Tok {
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Tok::Whitespace(_) => TokenKind::Whitespace,
+            Tok::Identifier(_) => TokenKind::Identifier,
+            Tok::Literal(_) => TokenKind::Literal,
+            Tok::Punct(p) => TokenKind::Punct(p.char()),
+        }
+    }
+}
+
 /// The result of a [`Parse::parse`] operation
 pub enum Munched<Token, Cursor> {
     /// Indicates that the parse operation succeeded and produced a `Token` as well as the next
@@ -27,7 +92,7 @@ pub enum Munched<Token, Cursor> {
     ///
     /// We intentionally do not bring in any explicit error type since this message should either:
     ///  - contain only a short, one line description about what error occurred, to be prettified by an
-    ///  outer function
+    ///    outer function
     ///
     /// **remarks:** do not use this to bubble errors produced by [`anyhow`], instead this should 
     /// be used exclusively to communicate that an error has occurred in the process of parsing, e.g.:
@@ -50,4 +115,176 @@ pub trait Munch {
     type Cursor;
 
     fn munch(&self, cursor: &Self::Cursor) -> anyhow::Result<Munched<Self::Token, Self::Cursor>>;
+
+    /// Repeatedly munch tokens starting at `start`, until either <eof> is reached or nothing more
+    /// can be munched
+    ///
+    /// A [`Munched::Err`] is returned as an [`anyhow::Error`], as is a token which does not move the
+    /// cursor forward, since munching again from the same cursor would never terminate
+    fn munch_all(&self, start: Self::Cursor) -> anyhow::Result<Vec<Self::Token>>
+    where
+        Self::Cursor: PartialEq,
+    {
+        let mut tokens = Vec::new();
+        let mut head = start;
+
+        loop {
+            match self.munch(&head)? {
+                Munched::Some(_, Some(next)) if next == head => {
+                    return Err(anyhow::anyhow!(
+                        "Failed to munch all tokens: munch made no progress"
+                    ));
+                }
+                Munched::Some(tok, next) => {
+                    tokens.push(tok);
+                    match next {
+                        Some(next) => head = next,
+                        None => break,
+                    }
+                }
+                Munched::Err(e) => return Err(anyhow::anyhow!(e)),
+                Munched::None => break,
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::marker::PhantomData;
+
+    use crate::{
+        cursor::Cursor,
+        memory_file::MemoryFile,
+        token::{
+            Identifier, Literal, Munch, MunchIdentifier, Munched, Punct, Tok, TokenKind, Tokenizer,
+            Whitespace,
+        },
+    };
+
+    #[test]
+    fn punct_exposes_char() {
+        let punct = Punct('+');
+
+        assert!(punct.char() == '+');
+        assert!(punct.is('+'));
+        assert!(!punct.is('-'));
+    }
+
+    #[test]
+    fn munch_all_single_token() {
+        let v = "foobar".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokens = MunchIdentifier::new().munch_all(head).unwrap();
+        assert!(tokens.len() == 1);
+        assert!(
+            matches!(tokens[0], Tok::Identifier(Identifier::Standard(ref s, _)) if s == "foobar")
+        );
+    }
+
+    #[test]
+    fn munch_all_stops_when_nothing_munches() {
+        let v = "foo bar".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokens = MunchIdentifier::new().munch_all(head).unwrap();
+        assert!(tokens.len() == 1);
+        assert!(matches!(tokens[0], Tok::Identifier(Identifier::Standard(ref s, _)) if s == "foo"));
+    }
+
+    #[test]
+    fn munch_all_surfaces_errors() {
+        let v = "r#1".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        assert!(MunchIdentifier::new().munch_all(head).is_err());
+    }
+
+    /// Munches an empty token without moving the cursor
+    struct MunchNothing<C>(PhantomData<C>);
+
+    impl<C: Cursor> Munch for MunchNothing<C> {
+        type Token = ();
+        type Cursor = C;
+
+        fn munch(&self, cursor: &C) -> anyhow::Result<Munched<(), C>> {
+            Ok(Munched::Some((), Some(cursor.clone())))
+        }
+    }
+
+    #[test]
+    fn munch_all_rejects_zero_width_tokens() {
+        let v = "foo".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        assert!(MunchNothing(PhantomData).munch_all(head).is_err());
+    }
+
+    #[test]
+    fn tokens_compare_equal() {
+        let v = "foo /* bar */ r#baz\n".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+
+        let a = Tokenizer::default()
+            .munch_all(f.head().unwrap().unwrap())
+            .unwrap();
+        let b = Tokenizer::default()
+            .munch_all(f.head().unwrap().unwrap())
+            .unwrap();
+
+        assert!(a.len() == 6);
+        assert!(a == b);
+        assert!(a[0] == Tok::Identifier(Identifier::Standard("foo".into(), None)));
+        assert!(a[0] != a[4]);
+    }
+
+    #[test]
+    fn kind_matches_variant() {
+        let tokens = [
+            Tok::Whitespace(Whitespace::Standard(" ".into())),
+            Tok::Identifier(Identifier::Raw("fn".into(), None)),
+            Tok::Literal(Literal::Integer(1, "1".into())),
+            Tok::Punct(Punct('+')),
+        ];
+        let kinds = tokens.iter().map(Tok::kind).collect::<Vec<TokenKind>>();
+
+        assert!(
+            kinds
+                == [
+                    TokenKind::Whitespace,
+                    TokenKind::Identifier,
+                    TokenKind::Literal,
+                    TokenKind::Punct('+'),
+                ]
+        );
+        assert!(Tok::Punct(Punct('-')).kind() != TokenKind::Punct('+'));
+    }
+
+    #[test]
+    fn display_reproduces_source() {
+        let input = "let r#fn 1.5e3 /* /* nested */ */ + foo_bar // trailing\n\t42\n";
+        let v = input.chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        // nothing munches punctuation yet, so tokenize either side of the `+`
+        let plus = input.find('+').unwrap();
+        let mut tokens = Tokenizer::default().munch_all(head.clone()).unwrap();
+        tokens.push(Tok::Punct(Punct('+')));
+        tokens.extend(
+            Tokenizer::default()
+                .munch_all(head.skip(plus + 1).unwrap().unwrap())
+                .unwrap(),
+        );
+
+        let output = tokens.iter().map(Tok::to_string).collect::<String>();
+        assert!(output == input, "{output:?}");
+    }
 }
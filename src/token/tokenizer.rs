@@ -0,0 +1,303 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    char_cursor_ext::CharCursorExt,
+    cursor::Cursor,
+    interner::Interner,
+    token::{Munch, MunchIdentifier, MunchNumber, MunchWhitespace, Munched, Tok, TokenizerConfig},
+};
+
+/// Munches any [`Tok`] by trying each of the token munchers in turn
+pub struct Tokenizer<C> {
+    config: TokenizerConfig,
+    whitespace: MunchWhitespace<C>,
+    identifier: MunchIdentifier<C>,
+    number: MunchNumber<C>,
+}
+
+impl<C> Tokenizer<C> {
+    pub fn new(config: TokenizerConfig) -> Self {
+        Self {
+            config,
+            whitespace: MunchWhitespace::with_config(config),
+            identifier: MunchIdentifier::with_keywords(config.keywords),
+            number: MunchNumber::new(),
+        }
+    }
+
+    /// Intern the name of every identifier munched into `interner`, see
+    /// [`MunchIdentifier::interning`]
+    pub fn interning(self, interner: Arc<Mutex<Interner>>) -> Self {
+        Self {
+            identifier: self.identifier.interning(interner),
+            ..self
+        }
+    }
+}
+
+impl<C: Cursor<Item = char>> Tokenizer<C> {
+    /// Skip a shebang line such as `#!/usr/bin/env allium`, returning the cursor which follows it,
+    /// or `start` itself if there is no shebang
+    ///
+    /// Only meaningful for the first cursor of a file. `#![` is not a shebang, so that it remains
+    /// available for inner attributes
+    pub fn skip_shebang(&self, start: &C) -> anyhow::Result<Option<C>> {
+        match start.lookahead_match("#!")? {
+            (true, Some(after)) if after.data()? != '[' => {
+                match after.skip_while(|c| *c != '\n')? {
+                    Some(line_feed) => line_feed.next(),
+                    None => Ok(None),
+                }
+            }
+            (true, None) => Ok(None),
+            _ => Ok(Some(start.clone())),
+        }
+    }
+}
+
+impl<C> Default for Tokenizer<C> {
+    fn default() -> Self {
+        Self::new(TokenizerConfig::default())
+    }
+}
+
+impl<C: Cursor<Item = char>> Munch for Tokenizer<C> {
+    type Token = Tok;
+    type Cursor = C;
+
+    fn munch(&self, cursor: &Self::Cursor) -> anyhow::Result<Munched<Self::Token, Self::Cursor>> {
+        let mut head = cursor.clone();
+        let mut errors = String::new();
+
+        loop {
+            match self.whitespace.munch(&head)? {
+                Munched::Some(tok, next) if self.config.keep_whitespace => {
+                    return Ok(Munched::Some(tok, next));
+                }
+                Munched::Some(_, Some(next)) => head = next,
+                Munched::Some(_, None) => return Ok(Munched::None),
+                Munched::Err(e) => {
+                    errors.push_str(e.as_str());
+                    break;
+                }
+                Munched::None => break,
+            }
+        }
+
+        match self.identifier.munch(&head)? {
+            Munched::Some(tok, next) => return Ok(Munched::Some(tok, next)),
+            Munched::Err(e) => {
+                if !errors.is_empty() {
+                    errors.push('\n');
+                }
+                errors.push_str(e.as_str());
+            }
+            Munched::None => {}
+        }
+
+        match self.number.munch(&head)? {
+            Munched::Some(tok, next) => return Ok(Munched::Some(tok, next)),
+            Munched::Err(e) => {
+                if !errors.is_empty() {
+                    errors.push('\n');
+                }
+                errors.push_str(e.as_str());
+            }
+            Munched::None => {}
+        }
+
+        if errors.is_empty() {
+            Ok(Munched::None)
+        } else {
+            Ok(Munched::Err(errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        cursor::Cursor,
+        interner::Interner,
+        memory_file::MemoryFile,
+        token::{Identifier, Literal, Munch, Munched, Tok, Tokenizer, TokenizerConfig, Whitespace},
+    };
+
+    #[test]
+    fn comment_escapes_are_configurable() {
+        let v = "/* \\/* */".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokenizer = Tokenizer::new(TokenizerConfig::default());
+        let res = tokenizer.munch(&head).unwrap();
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Whitespace(Whitespace::BlockComment(ref s)), None) if s == "/* \\/* */"
+        ));
+
+        let tokenizer = Tokenizer::new(TokenizerConfig {
+            comment_escapes: false,
+            ..Default::default()
+        });
+        let res = tokenizer.munch(&head).unwrap();
+        assert!(matches!(res, Munched::Err(_)));
+    }
+
+    #[test]
+    fn whitespace_can_be_skipped() {
+        let v = "/* a */ // b\n\tfoo".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokenizer = Tokenizer::default();
+        let res = tokenizer.munch(&head).unwrap();
+        assert!(matches!(res, Munched::Some(Tok::Whitespace(_), _)));
+
+        let tokenizer = Tokenizer::new(TokenizerConfig {
+            keep_whitespace: false,
+            ..Default::default()
+        });
+        let res = tokenizer.munch(&head).unwrap();
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Identifier(Identifier::Standard(ref s, _)), None) if s == "foo"
+        ));
+    }
+
+    #[test]
+    fn munches_numbers() {
+        let v = "x 1.5".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokenizer = Tokenizer::new(TokenizerConfig {
+            keep_whitespace: false,
+            ..Default::default()
+        });
+        let tokens = tokenizer.munch_all(head).unwrap();
+        assert!(
+            tokens
+                == [
+                    Tok::Identifier(Identifier::Standard("x".into(), None)),
+                    Tok::Literal(Literal::Decimal("1.5".into(), "1.5".into())),
+                ]
+        );
+    }
+
+    #[test]
+    fn shebang_is_skipped() {
+        let v = "#!/bin/allium\ncode".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokenizer = Tokenizer::default();
+        let head = tokenizer.skip_shebang(&head).unwrap().unwrap();
+        let tokens = tokenizer.munch_all(head).unwrap();
+        assert!(tokens == [Tok::Identifier(Identifier::Standard("code".into(), None))]);
+
+        let v = "#!/bin/allium".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        assert!(tokenizer.skip_shebang(&head).unwrap().is_none());
+    }
+
+    #[test]
+    fn inner_attribute_is_not_shebang() {
+        for input in ["#![attr]", "code"] {
+            let v = input.chars().collect::<Vec<char>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = f.head().unwrap().unwrap();
+
+            let next = Tokenizer::default().skip_shebang(&head).unwrap();
+            assert!(next == Some(head), "{input}");
+        }
+    }
+
+    #[test]
+    fn dot_is_not_munched_as_decimal() {
+        for (input, first) in [
+            ("1..2", Tok::Literal(Literal::Integer(1, "1".into()))),
+            (
+                "t.0",
+                Tok::Identifier(Identifier::Standard("t".into(), None)),
+            ),
+        ] {
+            let v = input.chars().collect::<Vec<char>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = f.head().unwrap().unwrap();
+
+            let tokens = Tokenizer::default().munch_all(head.clone()).unwrap();
+            assert!(tokens == [first], "{input}");
+
+            // nothing munches punctuation yet, but the `.` must not start a decimal either
+            for skip in 1..input.len() - 1 {
+                let res = Tokenizer::default().munch(&head.skip(skip).unwrap().unwrap());
+                assert!(matches!(res.unwrap(), Munched::None), "{input}");
+            }
+        }
+    }
+
+    #[test]
+    fn keywords_are_configurable() {
+        let v = "fn r#fn".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokens = Tokenizer::default().munch_all(head.clone()).unwrap();
+        assert!(tokens[0] == Tok::Identifier(Identifier::Standard("fn".into(), None)));
+
+        let tokenizer = Tokenizer::new(TokenizerConfig {
+            keep_whitespace: false,
+            keywords: &["fn", "let"],
+            ..Default::default()
+        });
+        let tokens = tokenizer.munch_all(head).unwrap();
+        assert!(
+            tokens
+                == [
+                    Tok::Identifier(Identifier::Keyword("fn".into(), None)),
+                    Tok::Identifier(Identifier::Raw("fn".into(), None)),
+                ]
+        );
+    }
+
+    #[test]
+    fn identifiers_can_be_interned() {
+        let v = "foo r#foo bar".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let tokens = Tokenizer::default().munch_all(head.clone()).unwrap();
+        assert!(tokens.iter().all(|t| match t {
+            Tok::Identifier(ident) => ident.symbol().is_none(),
+            _ => true,
+        }));
+
+        let interner = Arc::new(Mutex::new(Interner::new()));
+        let tokenizer = Tokenizer::new(TokenizerConfig {
+            keep_whitespace: false,
+            ..Default::default()
+        })
+        .interning(interner.clone());
+        let symbols = tokenizer
+            .munch_all(head)
+            .unwrap()
+            .iter()
+            .map(|t| match t {
+                Tok::Identifier(ident) => ident.symbol().unwrap(),
+                _ => panic!("Expected only identifiers"),
+            })
+            .collect::<Vec<_>>();
+
+        assert!(symbols.len() == 3);
+        assert!(symbols[0] == symbols[1]);
+        assert!(symbols[0] != symbols[2]);
+
+        let interner = interner.lock().unwrap();
+        assert!(interner.resolve(symbols[0]) == "foo");
+        assert!(interner.resolve(symbols[2]) == "bar");
+    }
+}
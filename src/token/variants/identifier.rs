@@ -1,37 +1,94 @@
-use std::marker::PhantomData;
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 use unicode_id_start::{is_id_continue, is_id_start};
 
 use crate::{
     char_cursor_ext::CharCursorExt,
     cursor::{Cursor, Seek},
+    interner::{Interner, Symbol},
     token::{Munch, Munched, Tok},
 };
 
 /// Any keyword or identifier-like token
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Identifier {
     /// Begins with either `_` or a character with the `XID_Start` unicode property
     /// After matching one such characters, continues collecting characters with the
     /// `XID_Continue` unicode property
     ///
-    /// Inner string
-    Standard(String),
+    /// Inner string, along with its [`Symbol`] if it was munched by an interning
+    /// [`MunchIdentifier`]
+    Standard(String, Option<Symbol>),
 
     /// Any valid identifier preceeded by the raw specifier (`r#`)
-    Raw(String),
+    Raw(String, Option<Symbol>),
+
+    /// A standard identifier which is in the keyword set of the [`MunchIdentifier`] that munched
+    /// it
+    ///
+    /// Raw identifiers are never keywords, so `r#fn` is always [`Identifier::Raw`]
+    Keyword(String, Option<Symbol>),
+}
+
+impl Identifier {
+    /// The name of this identifier, excluding any raw specifier
+    pub fn name(&self) -> &str {
+        match self {
+            Identifier::Standard(s, _) | Identifier::Raw(s, _) | Identifier::Keyword(s, _) => s,
+        }
+    }
+
+    /// The [`Symbol`] this identifier was interned to when it was munched, if any
+    pub fn symbol(&self) -> Option<Symbol> {
+        match self {
+            Identifier::Standard(_, sym)
+            | Identifier::Raw(_, sym)
+            | Identifier::Keyword(_, sym) => *sym,
+        }
+    }
+
+    /// Intern the name of this identifier, so that repeated identifiers can be referred to by
+    /// [`Symbol`] rather than by their own copy of the name
+    ///
+    /// `r#foo` and `foo` intern to the same [`Symbol`]
+    pub fn intern(&self, interner: &mut Interner) -> Symbol {
+        interner.intern(self.name())
+    }
 }
 
 pub struct MunchIdentifier<C> {
+    keywords: HashSet<String>,
+    interner: Option<Arc<Mutex<Interner>>>,
     _marker: PhantomData<C>,
 }
 
 impl<C> MunchIdentifier<C> {
-    fn new() -> Self {
+    pub fn new() -> Self {
+        Self::with_keywords(&[])
+    }
+
+    /// Munch identifiers found in `keywords` as [`Identifier::Keyword`] rather than
+    /// [`Identifier::Standard`]
+    pub fn with_keywords(keywords: &[&str]) -> Self {
         Self {
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            interner: None,
             _marker: PhantomData,
         }
     }
+
+    /// Intern the name of every identifier munched into `interner`, attaching the resulting
+    /// [`Symbol`] to the [`Identifier`]
+    pub fn interning(self, interner: Arc<Mutex<Interner>>) -> Self {
+        Self {
+            interner: Some(interner),
+            ..self
+        }
+    }
 }
 
 impl<C: Cursor<Item = char>> Munch for MunchIdentifier<C> {
@@ -52,27 +109,171 @@ impl<C: Cursor<Item = char>> Munch for MunchIdentifier<C> {
         let data = head.as_ref().unwrap().data()?;
 
         if data != '_' && !is_id_start(data) {
+            if is_raw {
+                return Ok(Munched::Err(
+                    "Failed to parse identifier: Found raw specifier but it was not followed by an identifier".into(),
+                ));
+            }
             return Ok(Munched::None);
         }
 
         let mut out = String::new();
 
-        while let Some(h) = head {
+        while let Some(h) = head.clone() {
             let data = h.data()?;
-            out.push(data);
-            head = h.next()?;
             if !is_id_continue(data) {
+                // don't advance head, we're at the first non-identifier character
                 break;
             }
+            out.push(data);
+            head = h.next()?;
         }
 
-        if is_raw {
-            Ok(Munched::Some(Tok::Identifier(Identifier::Raw(out)), head))
+        if is_raw && out == "_" {
+            return Ok(Munched::Err(
+                "Failed to parse identifier: `_` cannot be a raw identifier".into(),
+            ));
+        }
+
+        let sym = self
+            .interner
+            .as_ref()
+            .map(|i| i.lock().expect("Failed to get guard").intern(&out));
+
+        let ident = if is_raw {
+            Identifier::Raw(out, sym)
+        } else if self.keywords.contains(&out) {
+            Identifier::Keyword(out, sym)
         } else {
-            Ok(Munched::Some(
-                Tok::Identifier(Identifier::Standard(out)),
-                head,
-            ))
+            Identifier::Standard(out, sym)
+        };
+
+        Ok(Munched::Some(Tok::Identifier(ident), head))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cursor::Cursor,
+        interner::Interner,
+        memory_file::MemoryFile,
+        token::{Identifier, Munch, MunchIdentifier, Munched, Tok},
+    };
+
+    /// Munch an identifier from the start of `input`, discarding the next cursor so the result can
+    /// outlive the file
+    fn munch(input: &str) -> Munched<Tok, ()> {
+        let v = input.chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        match MunchIdentifier::new().munch(&head).unwrap() {
+            Munched::Some(tok, _) => Munched::Some(tok, None),
+            Munched::Err(e) => Munched::Err(e),
+            Munched::None => Munched::None,
         }
     }
+
+    #[test]
+    fn raw_keyword_is_identifier() {
+        let res = munch("r#fn");
+        assert!(
+            matches!(res, Munched::Some(Tok::Identifier(Identifier::Raw(ref s, _)), _) if s == "fn")
+        );
+    }
+
+    #[test]
+    fn raw_discard_is_error() {
+        assert!(matches!(munch("r#_"), Munched::Err(_)));
+        assert!(matches!(munch("r#_;"), Munched::Err(_)));
+        assert!(matches!(munch("r#_ foo"), Munched::Err(_)));
+        assert!(matches!(munch("r#_+"), Munched::Err(_)));
+        assert!(matches!(munch("r#_a;"), Munched::Some(_, _)));
+    }
+
+    #[test]
+    fn raw_non_identifier_is_error() {
+        assert!(matches!(munch("r#1"), Munched::Err(_)));
+        assert!(matches!(munch("r#+"), Munched::Err(_)));
+        assert!(matches!(munch("r# foo"), Munched::Err(_)));
+        assert!(matches!(munch("r#"), Munched::Err(_)));
+    }
+
+    #[test]
+    fn stops_before_non_identifier() {
+        let v = "abc;".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let res = MunchIdentifier::new().munch(&head).unwrap();
+        let Munched::Some(Tok::Identifier(Identifier::Standard(s, _)), Some(next)) = res else {
+            panic!("Expected standard identifier followed by more input");
+        };
+        assert!(s == "abc");
+        assert!(next.data().unwrap() == ';');
+    }
+
+    #[test]
+    fn keywords_are_configurable() {
+        let munch = MunchIdentifier::with_keywords(&["fn", "let", "if"]);
+
+        for (input, keyword) in [
+            ("fn", true),
+            ("let", true),
+            ("foo", false),
+            ("fnord", false),
+        ] {
+            let v = input.chars().collect::<Vec<char>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = f.head().unwrap().unwrap();
+
+            let res = munch.munch(&head).unwrap();
+            let Munched::Some(Tok::Identifier(ident), None) = res else {
+                panic!("Expected identifier: {input}");
+            };
+            assert!(ident.name() == input);
+            assert!(
+                matches!(ident, Identifier::Keyword(..)) == keyword,
+                "{input}"
+            );
+        }
+
+        let v = "fn".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let res = MunchIdentifier::new().munch(&head).unwrap();
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Identifier(Identifier::Standard(..)), None)
+        ));
+    }
+
+    #[test]
+    fn raw_keyword_is_not_keyword() {
+        let v = "r#fn".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let res = MunchIdentifier::with_keywords(&["fn"])
+            .munch(&head)
+            .unwrap();
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Identifier(Identifier::Raw(..)), _)
+        ));
+    }
+
+    #[test]
+    fn raw_and_standard_intern_together() {
+        let mut interner = Interner::new();
+
+        let standard = Identifier::Standard("foo".into(), None).intern(&mut interner);
+        let raw = Identifier::Raw("foo".into(), None).intern(&mut interner);
+        let other = Identifier::Standard("bar".into(), None).intern(&mut interner);
+
+        assert!(standard == raw);
+        assert!(standard != other);
+        assert!(interner.resolve(raw) == "foo");
+    }
 }
@@ -1,9 +1,17 @@
-use crate::token::Tok;
+use std::marker::PhantomData;
+
+use unicode_id_start::is_id_start;
+
+use crate::{
+    char_cursor_ext::CharCursorExt,
+    cursor::Cursor,
+    token::{Munch, Munched, Tok},
+};
 
 /// Representation of a literal of a given type
 ///
 /// TODO: refactored into multiple files
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Literal {
     // a character identifier begins with single quote(`'`)
     Char(u32, String),
@@ -13,5 +21,253 @@ pub enum Literal {
     ByteString(String, String),
     CString(Vec<u8>, String),
     Integer(u128, String),
+    /// A number with a fractional part and/or an exponent, e.g. `3.14`, `1.` or `1.5e-3`
+    ///
+    /// Decimals are not evaluated, so the value is the source text with any digit separators
+    /// removed, followed by the raw source text
     Decimal(String, String),
 }
+
+/// Munches [`Literal::Integer`] and [`Literal::Decimal`] tokens
+///
+/// A number always begins with a digit, so `.5` is not a decimal and `.` is left for the token
+/// before it to claim, as in `t.0` or `1..2`.
+///
+/// A `.` only continues a number if it is followed by a digit, or by something which could not
+/// continue an expression. So `1.5` and `1. ` are decimals, but in `1.foo`, `1._0` and `1..2` the
+/// `.` is left for the following token and `1` is munched as an integer.
+///
+/// Digits may be separated by single underscores, e.g. `1_000_000`. A separator must be
+/// surrounded by digits, so `1_`, `1__0` and `1_.5` are errors, and `_1` is not a number at all
+pub struct MunchNumber<C> {
+    _marker: PhantomData<C>,
+}
+
+impl<C> MunchNumber<C> {
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C: Cursor<Item = char>> Munch for MunchNumber<C> {
+    type Token = Tok;
+    type Cursor = C;
+
+    fn munch(&self, cursor: &Self::Cursor) -> anyhow::Result<Munched<Self::Token, Self::Cursor>> {
+        if !cursor.data()?.is_ascii_digit() {
+            return Ok(Munched::None);
+        }
+
+        let (mut out, mut head) = cursor.consume_while(is_digit_or_separator)?;
+        let mut runs = vec![out.clone()];
+        let mut is_decimal = false;
+
+        if let Some(h) = head.clone()
+            && h.data()? == '.'
+        {
+            let after = h.next()?;
+            let c = match &after {
+                Some(a) => Some(a.data()?),
+                None => None,
+            };
+
+            match c {
+                Some(c) if c.is_ascii_digit() => {
+                    let (fraction, next) = after.unwrap().consume_while(is_digit_or_separator)?;
+                    out.push('.');
+                    out.push_str(&fraction);
+                    runs.push(fraction);
+                    head = next;
+                    is_decimal = true;
+                }
+                // member access or range, leave the `.` for the next token
+                Some(c) if c == '.' || c == '_' || is_id_start(c) => {}
+                _ => {
+                    out.push('.');
+                    head = after;
+                    is_decimal = true;
+                }
+            }
+        }
+
+        if let Some(h) = head.clone()
+            && matches!(h.data()?, 'e' | 'E')
+        {
+            out.push(h.data()?);
+            let mut after = h.next()?;
+
+            if let Some(a) = after.clone()
+                && matches!(a.data()?, '+' | '-')
+            {
+                out.push(a.data()?);
+                after = a.next()?;
+            }
+
+            let (exponent, next) = match after {
+                Some(a) => a.consume_while(is_digit_or_separator)?,
+                None => (String::new(), None),
+            };
+            if !exponent.starts_with(|c: char| c.is_ascii_digit()) {
+                return Ok(Munched::Err(
+                    "Failed to parse number: Expected digits after exponent".into(),
+                ));
+            }
+            out.push_str(&exponent);
+            runs.push(exponent);
+            head = next;
+            is_decimal = true;
+        }
+
+        if runs
+            .iter()
+            .any(|run| run.ends_with('_') || run.contains("__"))
+        {
+            return Ok(Munched::Err(
+                "Failed to parse number: Digit separators must be between two digits".into(),
+            ));
+        }
+
+        let value = out.replace('_', "");
+        if is_decimal {
+            Ok(Munched::Some(
+                Tok::Literal(Literal::Decimal(value, out)),
+                head,
+            ))
+        } else {
+            match value.parse::<u128>() {
+                Ok(value) => Ok(Munched::Some(
+                    Tok::Literal(Literal::Integer(value, out)),
+                    head,
+                )),
+                Err(_) => Ok(Munched::Err(
+                    "Failed to parse integer: Value does not fit in 128 bits".into(),
+                )),
+            }
+        }
+    }
+}
+
+fn is_digit_or_separator(c: char) -> bool {
+    c.is_ascii_digit() || c == '_'
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        char_cursor_ext::CharCursorExt,
+        memory_file::MemoryFile,
+        token::{Literal, Munch, MunchNumber, Munched, Tok},
+    };
+
+    /// Munch a number from the start of `input`, returning the token along with the input which
+    /// was left over
+    fn munch(input: &str) -> Munched<Tok, String> {
+        let v = input.chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        match MunchNumber::new().munch(&head).unwrap() {
+            Munched::Some(tok, Some(next)) => {
+                Munched::Some(tok, Some(next.peek(input.len()).unwrap()))
+            }
+            Munched::Some(tok, None) => Munched::Some(tok, None),
+            Munched::Err(e) => Munched::Err(e),
+            Munched::None => Munched::None,
+        }
+    }
+
+    fn decimal(s: &str) -> Tok {
+        Tok::Literal(Literal::Decimal(s.into(), s.into()))
+    }
+
+    #[test]
+    fn decimal_forms() {
+        for input in ["3.14", "1.", "2e10", "1.5e-3", "6E+2"] {
+            let res = munch(input);
+            assert!(
+                matches!(res, Munched::Some(ref tok, None) if *tok == decimal(input)),
+                "{input}"
+            );
+        }
+
+        let Munched::Some(tok, Some(rest)) = munch("1. + 2") else {
+            panic!("Expected decimal followed by more input");
+        };
+        assert!(tok == decimal("1."));
+        assert!(rest == " + 2");
+    }
+
+    #[test]
+    fn integer() {
+        let Munched::Some(tok, Some(rest)) = munch("42;") else {
+            panic!("Expected integer followed by more input");
+        };
+        assert!(tok == Tok::Literal(Literal::Integer(42, "42".into())));
+        assert!(rest == ";");
+
+        assert!(matches!(
+            munch(&u128::MAX.to_string()),
+            Munched::Some(_, None)
+        ));
+        assert!(matches!(
+            munch("340282366920938463463374607431768211456"),
+            Munched::Err(_)
+        ));
+    }
+
+    #[test]
+    fn dot_after_integer_is_not_greedy() {
+        for (input, rest) in [
+            ("1.foo", ".foo"),
+            ("1..2", "..2"),
+            ("1._0", "._0"),
+            ("1.e5", ".e5"),
+        ] {
+            let Munched::Some(tok, Some(r)) = munch(input) else {
+                panic!("Expected integer followed by more input: {input}");
+            };
+            assert!(
+                tok == Tok::Literal(Literal::Integer(1, "1".into())),
+                "{input}"
+            );
+            assert!(r == rest, "{input}");
+        }
+    }
+
+    #[test]
+    fn not_a_number() {
+        assert!(matches!(munch("."), Munched::None));
+        assert!(matches!(munch(".5"), Munched::None));
+        assert!(matches!(munch("_1"), Munched::None));
+        assert!(matches!(munch(".foo"), Munched::None));
+        assert!(matches!(munch("foo"), Munched::None));
+    }
+
+    #[test]
+    fn exponent_requires_digits() {
+        assert!(matches!(munch("2e"), Munched::Err(_)));
+        assert!(matches!(munch("1.5e-"), Munched::Err(_)));
+        assert!(matches!(munch("2e+x"), Munched::Err(_)));
+    }
+
+    #[test]
+    fn digit_separators() {
+        let Munched::Some(tok, None) = munch("1_000") else {
+            panic!("Expected integer");
+        };
+        assert!(tok == Tok::Literal(Literal::Integer(1000, "1_000".into())));
+
+        let res = munch("1_000.000_5e1_0");
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Literal(Literal::Decimal(ref value, ref raw)), None)
+                if value == "1000.0005e10" && raw == "1_000.000_5e1_0"
+        ));
+
+        for input in ["1_", "1__0", "1_.5", "1.5_", "1e_5", "1e5_"] {
+            assert!(matches!(munch(input), Munched::Err(_)), "{input}");
+        }
+    }
+}
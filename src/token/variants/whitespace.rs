@@ -3,11 +3,11 @@ use std::marker::PhantomData;
 use crate::{
     char_cursor_ext::CharCursorExt,
     cursor::{Cursor, Seek},
-    token::{Munch, Munched, Tok},
+    token::{Munch, Munched, Tok, TokenizerConfig},
 };
 
 /// Any token which can be interpreted as whitespace
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Whitespace {
     /// contiguous sequence of characters contained within the Unicode Whitespace set:
     /// https://util.unicode.org/UnicodeJsps/list-unicodeset.jsp?a=[%3AWhitespace%3A]
@@ -20,21 +20,31 @@ pub enum Whitespace {
     LineComment(String),
     /// A block comment beginning with `/*` and ending with `*/`.
     ///
-    /// Block comments can be nested arbitrarilly deep, and will be parsed as a single token.
+    /// Block comments can be nested up to [`TokenizerConfig::max_comment_depth`] deep, and will be
+    /// parsed as a single token.
     /// As such, `/* /* */ *` would be parsed as a single [`Whitespace::BlockComment`]
     ///
     /// Block comment start and end characters may be escaped by preceeding the first character
-    /// with a backslash (`\`)
+    /// with a backslash (`\`). An escaped `\/*` or `\*/` is kept in the comment text but does not
+    /// change the nesting depth, so `/* \*/ */` is a single comment. Only a backslash directly
+    /// before a delimiter is an escape; backslashes cannot themselves be escaped, so `\\*/` is a
+    /// backslash followed by an escaped `\*/`.
     BlockComment(String),
 }
 
 pub struct MunchWhitespace<C> {
+    config: TokenizerConfig,
     _marker: PhantomData<C>,
 }
 
 impl<C> MunchWhitespace<C> {
-    fn new() -> Self {
+    pub fn new() -> Self {
+        Self::with_config(TokenizerConfig::default())
+    }
+
+    pub fn with_config(config: TokenizerConfig) -> Self {
         Self {
+            config,
             _marker: PhantomData,
         }
     }
@@ -70,7 +80,7 @@ where
             errors.push_str(e.as_str());
         }
 
-        let res = Whitespace::parse_block_comment(cursor)?;
+        let res = Whitespace::parse_block_comment(cursor, &self.config)?;
         if let Munched::Some(tok, next) = res {
             return Ok(Munched::Some(tok, next));
         } else if let Munched::Err(e) = res {
@@ -80,7 +90,11 @@ where
             errors.push_str(e.as_str());
         }
 
-        Ok(Munched::None)
+        if errors.is_empty() {
+            Ok(Munched::None)
+        } else {
+            Ok(Munched::Err(errors))
+        }
     }
 }
 
@@ -94,16 +108,19 @@ impl Whitespace {
 
         let mut head = Some(cursor.clone());
 
-        while let Some(h) = head {
+        while let Some(h) = head.clone() {
             let data = h.data()?;
-            out.push(h.data()?);
-            head = h.next()?;
             if !data.is_whitespace() {
+                // don't advance head, we're at first non-whitespace character
+                break;
+            }
+            out.push(data);
+            head = h.next()?;
+            if data == '\n' {
                 break;
             }
         }
 
-        // don't advance head, we're at first non-whitespace character
         Ok(Munched::Some(
             Tok::Whitespace(Whitespace::Standard(out)),
             head,
@@ -134,7 +151,10 @@ impl Whitespace {
         ))
     }
 
-    fn parse_block_comment<C: Cursor<Item = char>>(cursor: &C) -> anyhow::Result<Munched<Tok, C>> {
+    fn parse_block_comment<C: Cursor<Item = char>>(
+        cursor: &C,
+        config: &TokenizerConfig,
+    ) -> anyhow::Result<Munched<Tok, C>> {
         if matches!(cursor.lookahead_match("/*")?, (false, _)) {
             return Ok(Munched::None);
         }
@@ -146,15 +166,25 @@ impl Whitespace {
             if let (true, h) = h.lookahead_match("/*")? {
                 head = h;
                 depth += 1;
+                if depth > config.max_comment_depth {
+                    return Ok(Munched::Err(format!(
+                        "Failed to parse block comment: Nested more than {} deep",
+                        config.max_comment_depth
+                    )));
+                }
                 out.push_str("/*");
             } else if let (true, h) = h.lookahead_match("*/")? {
                 head = h;
                 depth -= 1;
                 out.push_str("*/")
-            } else if let (true, h) = h.lookahead_match("\\/*")? {
+            } else if let (true, h) = h.lookahead_match("\\/*")?
+                && config.comment_escapes
+            {
                 head = h;
                 out.push_str("\\/*");
-            } else if let (true, h) = h.lookahead_match("\\*/")? {
+            } else if let (true, h) = h.lookahead_match("\\*/")?
+                && config.comment_escapes
+            {
                 head = h;
                 out.push_str("\\*/");
             } else {
@@ -179,3 +209,90 @@ impl Whitespace {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cursor::Cursor,
+        memory_file::MemoryFile,
+        token::{Munch, MunchWhitespace, Munched, Tok, TokenizerConfig, Whitespace},
+    };
+
+    #[test]
+    fn standard_stops_before_non_whitespace() {
+        let v = "  \tfoo".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let res = MunchWhitespace::new().munch(&head).unwrap();
+        let Munched::Some(Tok::Whitespace(Whitespace::Standard(s)), Some(next)) = res else {
+            panic!("Expected standard whitespace followed by more input");
+        };
+        assert!(s == "  \t");
+        assert!(next.data().unwrap() == 'f');
+    }
+
+    #[test]
+    fn standard_stops_after_line_feed() {
+        let v = " \n\tfoo".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+
+        let res = MunchWhitespace::new().munch(&head).unwrap();
+        let Munched::Some(Tok::Whitespace(Whitespace::Standard(s)), Some(next)) = res else {
+            panic!("Expected standard whitespace followed by more input");
+        };
+        assert!(s == " \n");
+        assert!(next.data().unwrap() == '\t');
+    }
+
+    #[test]
+    fn block_comment_depth_is_limited() {
+        let config = TokenizerConfig {
+            max_comment_depth: 3,
+            ..Default::default()
+        };
+
+        let v = "/*/*/* */*/*/".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let res = MunchWhitespace::with_config(config).munch(&head).unwrap();
+        assert!(matches!(
+            res,
+            Munched::Some(Tok::Whitespace(Whitespace::BlockComment(_)), None)
+        ));
+
+        let v = "/*/*/*/* */*/*/*/".chars().collect::<Vec<char>>();
+        let f = MemoryFile::new(v.as_slice());
+        let head = f.head().unwrap().unwrap();
+        let res = MunchWhitespace::with_config(config).munch(&head).unwrap();
+        assert!(matches!(res, Munched::Err(_)));
+    }
+
+    #[test]
+    fn block_comment_escapes() {
+        for input in ["/* \\*/ */", "/*\\/**/"] {
+            let v = input.chars().collect::<Vec<char>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = f.head().unwrap().unwrap();
+
+            let res = MunchWhitespace::new().munch(&head).unwrap();
+            assert!(
+                matches!(
+                    res,
+                    Munched::Some(Tok::Whitespace(Whitespace::BlockComment(ref s)), None) if s == input
+                ),
+                "{input}"
+            );
+        }
+
+        for input in ["/* \\", "/* \\*/", "/* \\\\*/"] {
+            let v = input.chars().collect::<Vec<char>>();
+            let f = MemoryFile::new(v.as_slice());
+            let head = f.head().unwrap().unwrap();
+
+            let res = MunchWhitespace::new().munch(&head).unwrap();
+            assert!(matches!(res, Munched::Err(_)), "{input}");
+        }
+    }
+}
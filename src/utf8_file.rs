@@ -1,4 +1,8 @@
-use std::marker::PhantomData;
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 use crate::cursor::{Cursor, Seek};
 
@@ -43,62 +47,265 @@ impl From<UTF8Byte> for u8 {
 
 pub struct UTF8Cursor<C> {
     inner: C,
+    /// The byte cursor passed to [`UTF8Cursor::convert`], which `pos` is measured from
+    origin: C,
+    /// Offset in bytes of `inner` from `origin`
+    pos: usize,
+    /// 1-based line and column (in chars) of this cursor, a new line begins after each `\n`
+    line: usize,
+    col: usize,
+    /// Whether invalid sequences decode as U+FFFD rather than producing an error
+    lossy: bool,
+    /// Decoded chars shared between every cursor derived from the same [`UTF8Cursor::cached`] call
+    cache: Option<Arc<Mutex<DecodeCache<C>>>>,
 }
 
+/// The char decoded at a given byte offset, along with the inner cursor and byte offset of the
+/// char which follows it
+type DecodeCache<C> = HashMap<usize, (char, Option<(C, usize)>)>;
+
 impl<C: Clone> Clone for UTF8Cursor<C> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            origin: self.origin.clone(),
+            pos: self.pos,
+            line: self.line,
+            col: self.col,
+            lossy: self.lossy,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// Cursors are only comparable if they were converted from the same byte cursor, since their
+/// byte offsets are not otherwise measured from the same place
+impl<C: PartialEq> PartialEq for UTF8Cursor<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.origin == other.origin && self.inner == other.inner
+    }
+}
+
+impl<C: Eq> Eq for UTF8Cursor<C> {}
+
+impl<C: PartialOrd> PartialOrd for UTF8Cursor<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.origin == other.origin {
+            self.inner.partial_cmp(&other.inner)
+        } else {
+            None
         }
     }
 }
 
 impl<C: Cursor<Item = u8>> UTF8Cursor<C> {
-    pub fn convert(inner: C) -> anyhow::Result<Option<impl Cursor<Item = char>>> {
-        if let (next, '\u{FEFF}') = Self::deref(&inner)? {
-            Ok(next)
+    /// Adapt a byte cursor into a char cursor, skipping a leading byte order mark if present
+    ///
+    /// Invalid utf-8 is reported as an error by [`Cursor::data`] and [`Cursor::seek`]
+    pub fn convert(inner: C) -> anyhow::Result<Option<Self>> {
+        Self::start(inner, false)
+    }
+
+    /// Same as [`UTF8Cursor::convert`], but each invalid sequence decodes as a single
+    /// [`char::REPLACEMENT_CHARACTER`] and decoding resumes at the first byte which was not part of
+    /// it
+    pub fn convert_lossy(inner: C) -> anyhow::Result<Option<Self>> {
+        Self::start(inner, true)
+    }
+
+    /// Offset in bytes of this cursor from the byte cursor it was converted from
+    ///
+    /// A skipped byte order mark is included in the offset
+    pub fn byte_pos(&self) -> usize {
+        self.pos
+    }
+
+    /// 1-based line and column of this cursor
+    ///
+    /// Lines are separated by `\n` and columns count chars, so a multi-byte char occupies a single
+    /// column. Tracked as the cursor advances, so this never requires scanning the file
+    pub fn line_col(&self) -> (usize, usize) {
+        (self.line, self.col)
+    }
+
+    /// Memoize decoded chars, so that traversing the same bytes again with this cursor, or any
+    /// cursor derived from it, does not re-read or re-decode them
+    ///
+    /// Only successfully decoded chars are cached, errors are reproduced on each access
+    pub fn cached(self) -> Self {
+        Self {
+            cache: Some(Arc::new(Mutex::new(HashMap::new()))),
+            ..self
+        }
+    }
+
+    fn start(inner: C, lossy: bool) -> anyhow::Result<Option<Self>> {
+        let head = Self {
+            origin: inner.clone(),
+            inner,
+            pos: 0,
+            line: 1,
+            col: 1,
+            lossy,
+            cache: None,
+        };
+
+        if let (next, '\u{FEFF}') = head.deref()? {
+            // the byte order mark is not part of the text, so doesn't take up a column
+            Ok(next.map(|next| Self { col: 1, ..next }))
         } else {
-            Ok(Some(Self { inner }))
+            Ok(Some(head))
         }
     }
 
-    fn deref(inner: &C) -> anyhow::Result<(Option<Self>, char)> {
-        let mut head = inner.clone();
+    fn deref(&self) -> anyhow::Result<(Option<Self>, char)> {
+        let Some(cache) = &self.cache else {
+            return self.decode();
+        };
+
+        let hit = cache
+            .lock()
+            .expect("Failed to get guard")
+            .get(&self.pos)
+            .cloned();
+        if let Some((c, next)) = hit {
+            return Ok((next.map(|(inner, pos)| self.at(inner, pos, c)), c));
+        }
+
+        // decode without holding the guard, the inner cursor may well take locks of its own
+        let (next, c) = self.decode()?;
+        cache.lock().expect("Failed to get guard").insert(
+            self.pos,
+            (c, next.as_ref().map(|n| (n.inner.clone(), n.pos))),
+        );
 
-        let (length, mut val) = match UTF8Byte::from(head.data()?) {
+        Ok((next, c))
+    }
+
+    fn decode(&self) -> anyhow::Result<(Option<Self>, char)> {
+        let mut head = self.inner.clone();
+        let pos = self.pos;
+
+        let byte = head.data()?;
+        let (length, mut val) = match UTF8Byte::from(byte) {
             UTF8Byte::OneByte(v) => (1, v as u32),
             UTF8Byte::TwoByte(v) => (2, v as u32),
             UTF8Byte::ThreeByte(v) => (3, v as u32),
             UTF8Byte::FourByte(v) => (4, v as u32),
             _ => {
-                return Err(anyhow::anyhow!(
-                    "Cursor does not refer to a valid utf-8 start byte"
-                ));
+                return self.invalid(
+                    head.next()?,
+                    1,
+                    anyhow::anyhow!(
+                        "Cursor does not refer to a valid utf-8 start byte ({byte:#04X} at byte {pos})"
+                    ),
+                );
             }
         };
 
-        for _ in 1..length {
+        for i in 1..length {
             head = match head.next()? {
                 Some(c) => c,
-                None => return Err(anyhow::anyhow!("Reached <eof> while parsing utf-8 char")),
+                None => {
+                    return self.invalid(
+                        None,
+                        i,
+                        anyhow::anyhow!(
+                            "Reached <eof> while parsing utf-8 char starting at byte {pos}"
+                        ),
+                    );
+                }
             };
 
-            if let UTF8Byte::Continuation(v) = UTF8Byte::from(head.data()?) {
+            let byte = head.data()?;
+            if let UTF8Byte::Continuation(v) = UTF8Byte::from(byte) {
                 val <<= 6;
                 val |= v as u32;
             } else {
-                return Err(anyhow::anyhow!(
-                    "Cursor referred to a valid utf-8 start byte, but proceeding byte was not a continuation"
-                ));
+                return self.invalid(
+                    Some(head),
+                    i,
+                    anyhow::anyhow!(
+                        "Cursor referred to a valid utf-8 start byte, but proceeding byte was not a continuation ({byte:#04X} at byte {})",
+                        pos + i
+                    ),
+                );
             }
         }
-        let c = char::from_u32(val).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Cursor referred to a valid code-point, but it was a surrogate value ({val:#04X})"
-            )
-        })?;
 
-        Ok((head.next()?.map(|inner| Self { inner }), c))
+        let next = head.next()?;
+
+        // shortest encodings only, anything else could be used to sneak e.g. <nul> past a check
+        // which operates on the bytes
+        let min = match length {
+            1 => 0,
+            2 => 0x80,
+            3 => 0x800,
+            _ => 0x10000,
+        };
+        if val < min {
+            return self.invalid(
+                next,
+                length,
+                anyhow::anyhow!(
+                    "Cursor referred to an overlong utf-8 encoding of {val:#04X} ({length} bytes at byte {pos})"
+                ),
+            );
+        }
+
+        match char::from_u32(val) {
+            Some(c) => Ok((self.advance(next, length, c), c)),
+            None => self.invalid(
+                next,
+                length,
+                anyhow::anyhow!(
+                    "Cursor referred to a valid code-point, but it was a surrogate value ({val:#04X} at byte {pos})"
+                ),
+            ),
+        }
+    }
+
+    /// Wrap the byte cursor `length` bytes to the right of this one
+    fn advance(&self, next: Option<C>, length: usize, c: char) -> Option<Self> {
+        next.map(|inner| self.at(inner, self.pos + length, c))
+    }
+
+    /// Wrap `inner`, which is `pos` bytes from the converted cursor and follows `c`, the char at
+    /// this cursor, with the same settings as this cursor
+    fn at(&self, inner: C, pos: usize, c: char) -> Self {
+        let (line, col) = if c == '\n' {
+            (self.line + 1, 1)
+        } else {
+            (self.line, self.col + 1)
+        };
+
+        Self {
+            inner,
+            origin: self.origin.clone(),
+            pos,
+            line,
+            col,
+            lossy: self.lossy,
+            cache: self.cache.clone(),
+        }
+    }
+
+    /// Resolve an invalid sequence of `length` bytes, either as `err` or, for lossy cursors, as
+    /// the replacement character followed by `next`
+    fn invalid(
+        &self,
+        next: Option<C>,
+        length: usize,
+        err: anyhow::Error,
+    ) -> anyhow::Result<(Option<Self>, char)> {
+        if self.lossy {
+            Ok((
+                self.advance(next, length, char::REPLACEMENT_CHARACTER),
+                char::REPLACEMENT_CHARACTER,
+            ))
+        } else {
+            Err(err)
+        }
     }
 }
 
@@ -106,14 +313,14 @@ impl<C: Cursor<Item = u8>> Cursor for UTF8Cursor<C> {
     type Item = char;
 
     fn data(&self) -> anyhow::Result<Self::Item> {
-        UTF8Cursor::deref(&self.inner).map(|(_, c)| c)
+        self.deref().map(|(_, c)| c)
     }
 
     fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
         if let Seek::Right(mut x) = op {
             let mut head = self.clone();
             while x > 0 {
-                head = match UTF8Cursor::deref(&head.inner)? {
+                head = match head.deref()? {
                     (None, _) => return Ok(None),
                     (Some(h), _) => h,
                 };
@@ -122,7 +329,7 @@ impl<C: Cursor<Item = u8>> Cursor for UTF8Cursor<C> {
             Ok(Some(head))
         } else {
             Err(anyhow::anyhow!(
-                "Seek failed: Seek::Left is unsuported by this file"
+                "Seek failed: {op:?} is unsuported by this file"
             ))
         }
     }
@@ -130,7 +337,7 @@ impl<C: Cursor<Item = u8>> Cursor for UTF8Cursor<C> {
 
 #[cfg(test)]
 mod test {
-    use std::io::Read;
+    use std::{cell::Cell, io::Read, rc::Rc};
 
     use crate::{
         cursor::{Cursor, Seek},
@@ -205,4 +412,199 @@ mod test {
             "Chars ended, but not at end of file"
         );
     }
+
+    #[test]
+    fn file_reports_invalid_byte_position() {
+        let memory = [b'a', b'b', 0xFF, b'c'];
+        let byte_file = MemoryFile::new(memory.as_slice());
+        let byte_cursor = byte_file.head().unwrap().unwrap();
+        let cursor = UTF8Cursor::convert(byte_cursor).unwrap().unwrap();
+
+        let err = cursor.seek(Seek::Right(2)).unwrap().unwrap().data();
+        let message = err.unwrap_err().to_string();
+        assert!(message.contains("0xFF at byte 2"), "{message}");
+    }
+
+    #[test]
+    fn file_reports_invalid_continuation_position() {
+        let memory = "\u{FEFF}a€"
+            .bytes()
+            .chain([0xC3, 0x28])
+            .collect::<Vec<u8>>();
+        let byte_file = MemoryFile::new(memory.as_slice());
+        let byte_cursor = byte_file.head().unwrap().unwrap();
+        let cursor = UTF8Cursor::convert(byte_cursor).unwrap().unwrap();
+
+        let err = cursor.seek(Seek::Right(2)).unwrap().unwrap().data();
+        let message = err.unwrap_err().to_string();
+        assert!(message.contains("0x28 at byte 8"), "{message}");
+    }
+
+    #[test]
+    fn lossy_file_replaces_invalid_utf8() {
+        let memory = [0x48, 0xFF, 0x49, 0xE2, 0x82, 0x4A, 0xE2, 0x82];
+        let byte_file = MemoryFile::new(memory.as_slice());
+
+        let byte_cursor = byte_file.head().unwrap().unwrap();
+        let mut cursor = UTF8Cursor::convert_lossy(byte_cursor).unwrap();
+        let mut chars = Vec::new();
+        while let Some(c) = cursor {
+            chars.push(c.data().expect("Lossy cursor should not error"));
+            cursor = c.next().expect("Lossy cursor should not error");
+        }
+        assert!(
+            chars == ['H', '\u{FFFD}', 'I', '\u{FFFD}', 'J', '\u{FFFD}'],
+            "{chars:?}"
+        );
+
+        let byte_cursor = byte_file.head().unwrap().unwrap();
+        let cursor = UTF8Cursor::convert(byte_cursor).unwrap().unwrap();
+        assert!(cursor.next().unwrap().unwrap().data().is_err());
+    }
+
+    #[test]
+    fn file_rejects_overlong_utf8() {
+        for memory in [
+            [0xC0, 0x80].as_slice(),
+            &[0xC1, 0xBF],
+            &[0xE0, 0x80, 0x80],
+            &[0xE0, 0x9F, 0xBF],
+            &[0xF0, 0x80, 0x80, 0x80],
+            &[0xF0, 0x8F, 0xBF, 0xBF],
+        ] {
+            let byte_file = MemoryFile::new(memory);
+            let byte_cursor = byte_file.head().unwrap().unwrap();
+            let err = UTF8Cursor::convert(byte_cursor).err();
+            assert!(
+                err.is_some_and(|e| e.to_string().contains("overlong")),
+                "{memory:02X?}"
+            );
+
+            let byte_cursor = byte_file.head().unwrap().unwrap();
+            let cursor = UTF8Cursor::convert_lossy(byte_cursor).unwrap().unwrap();
+            assert!(cursor.data().unwrap() == '\u{FFFD}');
+            assert!(cursor.next().unwrap().is_none());
+        }
+
+        for c in [
+            '\u{0}',
+            '\u{7F}',
+            '\u{80}',
+            '\u{7FF}',
+            '\u{800}',
+            '\u{FFFF}',
+            '\u{10000}',
+            '\u{10FFFF}',
+        ] {
+            let memory = c.to_string().into_bytes();
+            let byte_file = MemoryFile::new(memory.as_slice());
+            let byte_cursor = byte_file.head().unwrap().unwrap();
+            let cursor = UTF8Cursor::convert(byte_cursor).unwrap().unwrap();
+            assert!(cursor.data().unwrap() == c);
+        }
+    }
+
+    /// Counts calls to [`Cursor::data`] on the wrapped cursor
+    #[derive(Clone)]
+    struct CountingCursor<C> {
+        inner: C,
+        reads: Rc<Cell<usize>>,
+    }
+
+    impl<C: Cursor> Cursor for CountingCursor<C> {
+        type Item = C::Item;
+
+        fn data(&self) -> anyhow::Result<Self::Item> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.data()
+        }
+
+        fn seek(&self, op: Seek) -> anyhow::Result<Option<Self>> {
+            Ok(self.inner.seek(op)?.map(|inner| Self {
+                inner,
+                reads: self.reads.clone(),
+            }))
+        }
+    }
+
+    #[test]
+    fn cached_file_reuses_decoded_chars() {
+        let string = "a€b\u{10348}c";
+        let memory = string.bytes().collect::<Vec<u8>>();
+        let byte_file = MemoryFile::new(memory.as_slice());
+        let reads = Rc::new(Cell::new(0));
+        let byte_cursor = CountingCursor {
+            inner: byte_file.head().unwrap().unwrap(),
+            reads: reads.clone(),
+        };
+        let head = UTF8Cursor::convert(byte_cursor).unwrap().unwrap().cached();
+
+        let walk = || {
+            let mut out = String::new();
+            let mut cursor = Some(head.clone());
+            while let Some(c) = cursor {
+                out.push(c.data().unwrap());
+                cursor = c.next().unwrap();
+            }
+            out
+        };
+
+        assert!(walk() == string);
+        let first = reads.get();
+        assert!(first >= string.len(), "{first}");
+
+        assert!(walk() == string);
+        assert!(reads.get() == first, "{} != {first}", reads.get());
+    }
+
+    #[test]
+    fn cursor_reports_byte_pos() {
+        for (string, expected) in [("a€b", [0, 1, 4]), ("\u{FEFF}a€b", [3, 4, 7])] {
+            let memory = string.bytes().collect::<Vec<u8>>();
+            let byte_file = MemoryFile::new(memory.as_slice());
+            let byte_cursor = byte_file.head().unwrap().unwrap();
+
+            let mut positions = Vec::new();
+            let mut cursor = UTF8Cursor::convert(byte_cursor).unwrap();
+            while let Some(c) = cursor {
+                positions.push(c.byte_pos());
+                cursor = c.next().unwrap();
+            }
+
+            assert!(positions == expected, "{positions:?}");
+        }
+    }
+
+    #[test]
+    fn cursor_reports_line_col() {
+        let string = "\u{FEFF}ab\n€\n\nxyz";
+        let memory = string.bytes().collect::<Vec<u8>>();
+        let byte_file = MemoryFile::new(memory.as_slice());
+        let byte_cursor = byte_file.head().unwrap().unwrap();
+
+        let mut positions = Vec::new();
+        let mut cursor = UTF8Cursor::convert(byte_cursor)
+            .unwrap()
+            .map(UTF8Cursor::cached);
+        while let Some(c) = cursor {
+            positions.push((c.data().unwrap(), c.line_col()));
+            cursor = c.next().unwrap();
+        }
+
+        assert!(
+            positions
+                == [
+                    ('a', (1, 1)),
+                    ('b', (1, 2)),
+                    ('\n', (1, 3)),
+                    ('€', (2, 1)),
+                    ('\n', (2, 2)),
+                    ('\n', (3, 1)),
+                    ('x', (4, 1)),
+                    ('y', (4, 2)),
+                    ('z', (4, 3)),
+                ],
+            "{positions:?}"
+        );
+    }
 }